@@ -3,32 +3,154 @@
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
+use itertools::Itertools;
+use strum_macros::EnumString;
+
 use crate::card::Card;
 use crate::resources::Resource;
+use crate::utils::plural;
 
 /// Represents an action.
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub enum Action {
     Build(Card, Borrowing),
     Wonder(Card, Borrowing),
+    /// Builds the card for free, using the player's once-per-age free build (the Statue of Zeus in Olympia, side A).
+    FreeBuild(Card),
     Discard(Card),
 }
 
+impl Action {
+    /// Returns the card used by this action.
+    pub fn card(&self) -> &Card {
+        match self {
+            Action::Build(card, _) | Action::Wonder(card, _) | Action::FreeBuild(card) | Action::Discard(card) => card,
+        }
+    }
+}
+
 impl Display for Action {
     /// Formats the action, returning only public information. For example, if the action is to discard a card, the
-    /// card in question is not revealed.
-    /// TODO: Handle borrowing. In which case we probably need a different function that can take the player index and
-    ///  return things like "Build Baths by paying 2 coins to player 3 to borrow stone".
+    /// card in question is not revealed. See [`RevealedAction`] for formatting an action including the coins paid to
+    /// neighbours.
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            Action::Build(card, _) => write!(f, "Build {}", card.to_string()),
+            Action::Build(card, _) => write!(f, "Build {}", card),
             Action::Wonder(_, _) => write!(f, "Build a wonder stage"),
+            Action::FreeBuild(card) => write!(f, "Build {} for free", card),
             Action::Discard(_) => write!(f, "Discard"),
         }
     }
 }
 
+/// Why a player can't perform an action (see [`crate::player::Player::check_action`]).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, EnumString)]
+pub enum IllegalActionReason {
+    /// The card used by the action isn't in the player's hand.
+    CardNotInHand,
+    /// The player has already built the same structure.
+    AlreadyBuilt,
+    /// Every stage of the player's wonder has already been built.
+    WonderComplete,
+    /// The player doesn't have a free build available this age.
+    NoFreeBuild,
+    /// The cost can't be paid using the player's own resources and the given borrowing (which must be empty for a
+    /// structure built by chaining).
+    CannotAfford,
+}
+
+impl Display for IllegalActionReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                IllegalActionReason::CardNotInHand => "the card is not in hand",
+                IllegalActionReason::AlreadyBuilt => "the structure is already built",
+                IllegalActionReason::WonderComplete => "the wonder is complete",
+                IllegalActionReason::NoFreeBuild => "no free build is available",
+                IllegalActionReason::CannotAfford => "the cost can't be paid",
+            }
+        )
+    }
+}
+
+/// Coins paid by one player to another, for example to borrow resources. The payer's coins are deducted when their
+/// action is performed, but the recipient is only credited once every player has acted, at the end of the turn, so
+/// coins received can't be spent in the same turn.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct CoinTransfer {
+    /// The index of the paying player.
+    pub from: usize,
+    /// The index of the player receiving the coins.
+    pub to: usize,
+    pub coins: i32,
+}
+
+/// The public part of an [`Action`], which is all other players get to see. Cards used to build wonder stages and
+/// discarded cards are kept face down, so are not revealed.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum PublicAction {
+    Build(Card),
+    Wonder,
+    FreeBuild(Card),
+    Discard,
+    /// Builds the card for free from the discard pile (the Mausoleum of Halicarnassus).
+    BuildFromDiscard(Card),
+}
+
+impl From<&Action> for PublicAction {
+    fn from(action: &Action) -> PublicAction {
+        match action {
+            Action::Build(card, _) => PublicAction::Build(*card),
+            Action::Wonder(_, _) => PublicAction::Wonder,
+            Action::FreeBuild(card) => PublicAction::FreeBuild(*card),
+            Action::Discard(_) => PublicAction::Discard,
+        }
+    }
+}
+
+/// An action performed by a player, as revealed to every player.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct RevealedAction {
+    /// The turn the action was performed in.
+    pub turn: u32,
+    /// The index of the player performing the action.
+    pub player_index: usize,
+    pub action: PublicAction,
+    /// The coins paid to neighbours to borrow resources for the action.
+    pub payments: Vec<CoinTransfer>,
+    /// Whether the action was an extra action with the final card of an age (the Hanging Gardens of Babylon).
+    pub seventh_card: bool,
+}
+
+impl Display for RevealedAction {
+    /// Formats the action, eg. "Player 2: Build Barracks, paying 2 coins to player 1".
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Player {}: ", self.player_index + 1)?;
+        match &self.action {
+            PublicAction::Build(card) => write!(f, "Build {}", card)?,
+            PublicAction::Wonder => write!(f, "Build a wonder stage")?,
+            PublicAction::FreeBuild(card) => write!(f, "Build {} for free", card)?,
+            PublicAction::Discard => write!(f, "Discard")?,
+            PublicAction::BuildFromDiscard(card) => write!(f, "Build {} from the discard pile", card)?,
+        }
+        let payments = self
+            .payments
+            .iter()
+            .map(|payment| format!("{} to player {}", plural(payment.coins, "coin"), payment.to + 1))
+            .join(" and ");
+        if !payments.is_empty() {
+            write!(f, ", paying {}", payments)?;
+        }
+        if self.seventh_card {
+            write!(f, " (seventh card)")?;
+        }
+        Ok(())
+    }
+}
+
 /// Represents the possible actions a player can take in order to lay a particular card.
 pub struct ActionOptions {
     pub actions: Vec<Action>,
@@ -40,12 +162,13 @@ impl ActionOptions {
         !self.actions.is_empty()
     }
 
-    /// Returns `true` if the card can be laid using the player's own built structures only (no borrowing).
+    /// Returns `true` if the card can be laid (or the wonder stage built) using the player's own built structures only
+    /// (no borrowing).
     pub fn own_cards_only(&self) -> bool {
         if self.actions.len() != 1 {
             return false;
         }
-        if let Action::Build(_, borrowing) = &self.actions[0] {
+        if let Action::Build(_, borrowing) | Action::Wonder(_, borrowing) = &self.actions[0] {
             if !borrowing.has_borrowing() {
                 return true;
             }
@@ -128,4 +251,37 @@ mod tests {
         };
         assert_eq!(false, options.own_cards_only());
     }
+
+    #[test]
+    fn public_action_hides_wonder_and_discarded_cards() {
+        let borrowing = Borrowing::no_borrowing();
+        assert_eq!(
+            PublicAction::Build(Card::Baths),
+            PublicAction::from(&Action::Build(Card::Baths, borrowing.clone()))
+        );
+        assert_eq!(
+            PublicAction::Wonder,
+            PublicAction::from(&Action::Wonder(Card::Baths, borrowing))
+        );
+        assert_eq!(PublicAction::Discard, PublicAction::from(&Action::Discard(Card::Baths)));
+    }
+
+    #[test]
+    fn revealed_action_formats_payments() {
+        let revealed_action = RevealedAction {
+            turn: 3,
+            player_index: 1,
+            action: PublicAction::Build(Card::Barracks),
+            payments: vec![CoinTransfer {
+                from: 1,
+                to: 0,
+                coins: 2,
+            }],
+            seventh_card: false,
+        };
+        assert_eq!(
+            "Player 2: Build Barracks, paying 2 coins to player 1",
+            revealed_action.to_string()
+        );
+    }
 }
@@ -0,0 +1,476 @@
+//! Tracks what a player knows about the hands of cards in circulation during an age, so playing algorithms can deduce
+//! their opponents' hands.
+//!
+//! Each hand passes around the table, clockwise in the first and third ages and anti-clockwise in the second. Once a
+//! hand has passed through our own hands, we know which cards it could contain: the cards we saw, less those built by
+//! the players holding it since. Cards discarded or used to build wonder stages are hidden, so only reduce the number
+//! of cards in the hand.
+
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+
+use crate::action::{Action, PublicAction};
+use crate::card;
+use crate::card::{Age, Card};
+use crate::game::VisibleGame;
+use crate::player::Player;
+
+/// What is known about a single hand of cards in circulation.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TrackedHand {
+    /// The cards the hand could contain. Empty if we've not seen the hand this age.
+    cards: Vec<Card>,
+    /// The number of cards in the hand.
+    size: usize,
+}
+
+#[allow(dead_code)]
+impl TrackedHand {
+    fn unseen(size: usize) -> TrackedHand {
+        TrackedHand { cards: vec![], size }
+    }
+
+    fn exact(cards: &[Card]) -> TrackedHand {
+        TrackedHand {
+            cards: cards.to_vec(),
+            size: cards.len(),
+        }
+    }
+
+    /// Returns the cards the hand could contain. Empty if the hand hasn't been seen this age.
+    pub fn cards(&self) -> &Vec<Card> {
+        &self.cards
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns `true` if we've seen this hand this age, and therefore know which cards it could contain.
+    pub fn seen(&self) -> bool {
+        !self.cards.is_empty() || self.size == 0
+    }
+
+    /// Returns `true` if we know exactly which cards are in the hand.
+    pub fn known(&self) -> bool {
+        self.seen() && self.cards.len() == self.size
+    }
+
+    /// Removes the given card, which has been revealed as played from this hand.
+    fn remove(&mut self, card: &Card) {
+        if let Some(position) = self.cards.iter().position(|c| c == card) {
+            self.cards.swap_remove(position);
+        }
+        self.remove_hidden();
+    }
+
+    /// Removes a card we don't know the identity of from this hand.
+    fn remove_hidden(&mut self) {
+        self.size = self.size.saturating_sub(1);
+        if self.size == 0 {
+            self.cards.clear();
+        }
+    }
+}
+
+/// Tracks the hands in circulation from the point of view of one player. Call [`CardTracker::update`] each time the
+/// player is asked for an action, and [`CardTracker::played`] with the action chosen.
+#[derive(Debug, Default)]
+pub struct CardTracker {
+    /// The turn the hands were last updated for.
+    turn: Option<u32>,
+    /// The hands in circulation, indexed by the player holding each hand on `turn`.
+    hands: Vec<TrackedHand>,
+    /// The cards of the current age we know aren't in any unseen hand: the cards in hands we've seen (including those
+    /// since played from them), and the cards built from hands we haven't seen.
+    seen_cards: HashMap<Card, u32>,
+    /// Whether the card played by our player on `turn` has already been removed from their hand.
+    own_card_removed: bool,
+    /// The number of actions revealed on `turn` that have already been applied to the hands. Actions are revealed
+    /// before the end of a turn if we're asked to play the seventh card of an age.
+    revealed_applied: usize,
+}
+
+#[allow(dead_code)]
+impl CardTracker {
+    pub fn new() -> CardTracker {
+        CardTracker::default()
+    }
+
+    /// Updates the tracked hands with everything revealed since the last update, and with the player's current hand.
+    pub fn update(&mut self, player: &Player, visible_game: &VisibleGame) {
+        let turn = visible_game.turn;
+        match self.turn {
+            // Asked again on the same turn: to play the seventh card of an age once the other actions are revealed, or
+            // to replace an illegal action.
+            Some(tracked_turn) if tracked_turn == turn => self.apply_revealed(visible_game, turn),
+            // The next turn of the same age.
+            Some(tracked_turn) if tracked_turn + 1 == turn && !turn.is_multiple_of(6) => self.advance(visible_game),
+            // A new age, or we've missed turns.
+            _ => self.reset(player, visible_game),
+        }
+
+        // We can see our own hand. If it's a hand we hadn't seen yet this age, all its cards are now accounted for.
+        let own_hand = &mut self.hands[visible_game.player_index];
+        if !own_hand.seen() {
+            for card in player.hand() {
+                *self.seen_cards.entry(*card).or_insert(0) += 1;
+            }
+        }
+        *own_hand = TrackedHand::exact(player.hand());
+        self.turn = Some(turn);
+        self.own_card_removed = false;
+    }
+
+    /// Records the action our player chose this turn. This lets us know what's left in the hand we pass on, even if the
+    /// action is hidden from other players.
+    pub fn played(&mut self, player_index: usize, action: &Action) {
+        if let Some(hand) = self.hands.get_mut(player_index) {
+            hand.remove(action.card());
+            self.own_card_removed = true;
+        }
+    }
+
+    /// Returns what's known about the hand held by the player with the given index.
+    pub fn hand(&self, player_index: usize) -> &TrackedHand {
+        &self.hands[player_index]
+    }
+
+    /// Deals a hand to every player consistent with what we know: our player gets their actual hand, hands we've seen
+    /// get cards chosen from those they could contain, and the remaining hands get cards chosen from those not yet
+    /// accounted for. Intended for algorithms that simulate the rest of the game.
+    pub fn sample_hands(&self, player: &Player, visible_game: &VisibleGame, rng: &mut ChaCha8Rng) -> Vec<Vec<Card>> {
+        let age = match visible_game.age() {
+            Ok(age) => age,
+            Err(_) => return vec![vec![]; visible_game.public_players.len()],
+        };
+        let player_count = visible_game.public_players.len() as u32;
+        let mut unseen_cards = card::new_deck_without(&age, player_count, &self.seen_cards, rng);
+        self.hands
+            .iter()
+            .enumerate()
+            .map(|(index, hand)| {
+                if index == visible_game.player_index {
+                    player.hand().clone()
+                } else if hand.seen() {
+                    hand.cards.choose_multiple(rng, hand.size).copied().collect()
+                } else {
+                    let size = hand.size.min(unseen_cards.len());
+                    unseen_cards.drain(0..size).collect()
+                }
+            })
+            .collect()
+    }
+
+    /// Starts tracking from the current turn, knowing nothing other than the player's hand and the cards already built
+    /// this age.
+    fn reset(&mut self, player: &Player, visible_game: &VisibleGame) {
+        let age = visible_game.age().ok();
+        self.hands = vec![TrackedHand::unseen(player.hand().len()); visible_game.public_players.len()];
+        self.seen_cards = HashMap::new();
+        self.revealed_applied = 0;
+        for public_player in visible_game.public_players {
+            for card in &public_player.built_structures {
+                if Some(card.age()) == age.as_ref() {
+                    *self.seen_cards.entry(*card).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    /// Applies the actions revealed on the tracked turn to the hands they were played from, then passes the hands on.
+    fn advance(&mut self, visible_game: &VisibleGame) {
+        self.apply_revealed(visible_game, visible_game.turn - 1);
+        self.revealed_applied = 0;
+
+        if visible_game.age() == Ok(Age::Second) {
+            // Passed anti-clockwise, so each player receives the hand of the player on their left.
+            self.hands.rotate_left(1);
+        } else {
+            // Passed clockwise, so each player receives the hand of the player on their right.
+            self.hands.rotate_right(1);
+        }
+    }
+
+    /// Applies the actions revealed on the given turn, other than those already applied, to the hands they were played
+    /// from.
+    fn apply_revealed(&mut self, visible_game: &VisibleGame, turn: u32) {
+        for revealed_action in visible_game.history_for_turn(turn).skip(self.revealed_applied) {
+            self.revealed_applied += 1;
+            let index = revealed_action.player_index;
+            if index == visible_game.player_index && self.own_card_removed {
+                continue;
+            }
+            let hand = &mut self.hands[index];
+            match &revealed_action.action {
+                PublicAction::Build(card) | PublicAction::FreeBuild(card) => {
+                    if !hand.seen() {
+                        *self.seen_cards.entry(*card).or_insert(0) += 1;
+                    }
+                    hand.remove(card);
+                }
+                PublicAction::Wonder | PublicAction::Discard => hand.remove_hidden(),
+                // Built from the discard pile, not from a hand.
+                PublicAction::BuildFromDiscard(_) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::Borrowing;
+    use crate::action::RevealedAction;
+    use crate::player::PublicPlayer;
+    use crate::utils::derive_rng;
+    use crate::wonder::{WonderSide, WonderType};
+    use itertools::Itertools;
+    use Card::*;
+
+    #[test]
+    fn update_knows_the_hand_passed_clockwise_in_the_first_age() {
+        let public_players = public_players();
+        let mut tracker = CardTracker::new();
+        let history = vec![
+            revealed(0, 0, PublicAction::Build(Barracks)),
+            revealed(0, 1, PublicAction::Build(LumberYard)),
+            revealed(0, 2, PublicAction::Discard),
+        ];
+
+        update(
+            &mut tracker,
+            &public_players,
+            &history,
+            0,
+            vec![LumberYard, StonePit, ClayPool],
+        );
+        tracker.played(1, &Action::Build(LumberYard, Borrowing::no_borrowing()));
+        update(&mut tracker, &public_players, &history, 1, vec![Altar, Baths]);
+
+        assert!(tracker.hand(2).known());
+        assert_eq!(sorted(tracker.hand(2).cards()), sorted(&[StonePit, ClayPool]));
+        assert!(tracker.hand(1).known());
+        assert_eq!(tracker.hand(1).cards(), &vec![Altar, Baths]);
+        assert!(!tracker.hand(0).seen());
+        assert_eq!(tracker.hand(0).size(), 2);
+    }
+
+    #[test]
+    fn update_knows_the_hand_passed_anti_clockwise_in_the_second_age() {
+        let public_players = public_players();
+        let mut tracker = CardTracker::new();
+        let history = vec![
+            revealed(6, 0, PublicAction::Discard),
+            revealed(6, 1, PublicAction::Wonder),
+            revealed(6, 2, PublicAction::Discard),
+        ];
+
+        update(
+            &mut tracker,
+            &public_players,
+            &history,
+            6,
+            vec![Sawmill, Quarry, Brickyard],
+        );
+        tracker.played(1, &Action::Wonder(Quarry, Borrowing::no_borrowing()));
+        update(&mut tracker, &public_players, &history, 7, vec![Loom2, Glassworks2]);
+
+        assert!(tracker.hand(0).known());
+        assert_eq!(sorted(tracker.hand(0).cards()), sorted(&[Sawmill, Brickyard]));
+        assert!(!tracker.hand(2).seen());
+    }
+
+    #[test]
+    fn update_removes_cards_built_from_a_known_hand() {
+        let public_players = public_players();
+        let mut tracker = CardTracker::new();
+        let history = vec![
+            revealed(0, 0, PublicAction::Discard),
+            revealed(0, 1, PublicAction::Build(LumberYard)),
+            revealed(0, 2, PublicAction::Discard),
+            revealed(1, 0, PublicAction::Discard),
+            revealed(1, 1, PublicAction::Build(Altar)),
+            revealed(1, 2, PublicAction::Build(StonePit)),
+        ];
+
+        update(
+            &mut tracker,
+            &public_players,
+            &history,
+            0,
+            vec![LumberYard, StonePit, ClayPool],
+        );
+        tracker.played(1, &Action::Build(LumberYard, Borrowing::no_borrowing()));
+        update(&mut tracker, &public_players, &history, 1, vec![Altar, Baths]);
+        tracker.played(1, &Action::Build(Altar, Borrowing::no_borrowing()));
+        update(&mut tracker, &public_players, &history, 2, vec![Theater]);
+
+        assert!(tracker.hand(0).known());
+        assert_eq!(tracker.hand(0).cards(), &vec![ClayPool]);
+        assert!(tracker.hand(2).known());
+        assert_eq!(tracker.hand(2).cards(), &vec![Baths]);
+    }
+
+    #[test]
+    fn update_keeps_the_possible_cards_of_a_hand_after_a_hidden_action() {
+        let public_players = public_players();
+        let mut tracker = CardTracker::new();
+        let history = vec![
+            revealed(0, 0, PublicAction::Discard),
+            revealed(0, 1, PublicAction::Build(LumberYard)),
+            revealed(0, 2, PublicAction::Discard),
+            revealed(1, 0, PublicAction::Discard),
+            revealed(1, 1, PublicAction::Build(Altar)),
+            revealed(1, 2, PublicAction::Discard),
+        ];
+
+        update(
+            &mut tracker,
+            &public_players,
+            &history,
+            0,
+            vec![LumberYard, StonePit, ClayPool],
+        );
+        tracker.played(1, &Action::Build(LumberYard, Borrowing::no_borrowing()));
+        update(&mut tracker, &public_players, &history, 1, vec![Altar, Baths]);
+        tracker.played(1, &Action::Build(Altar, Borrowing::no_borrowing()));
+        update(&mut tracker, &public_players, &history, 2, vec![Theater]);
+
+        assert!(tracker.hand(0).seen());
+        assert!(!tracker.hand(0).known());
+        assert_eq!(tracker.hand(0).size(), 1);
+        assert_eq!(sorted(tracker.hand(0).cards()), sorted(&[StonePit, ClayPool]));
+    }
+
+    #[test]
+    fn update_forgets_the_hands_at_the_start_of_a_new_age() {
+        let public_players = public_players();
+        let mut tracker = CardTracker::new();
+        let history = vec![
+            revealed(5, 0, PublicAction::Discard),
+            revealed(5, 1, PublicAction::Build(LumberYard)),
+            revealed(5, 2, PublicAction::Discard),
+        ];
+
+        update(&mut tracker, &public_players, &history, 5, vec![LumberYard, StonePit]);
+        tracker.played(1, &Action::Build(LumberYard, Borrowing::no_borrowing()));
+        update(
+            &mut tracker,
+            &public_players,
+            &history,
+            6,
+            vec![Sawmill, Quarry, Brickyard],
+        );
+
+        assert!(!tracker.hand(0).seen());
+        assert!(!tracker.hand(2).seen());
+        assert_eq!(tracker.hand(2).size(), 3);
+        assert_eq!(tracker.hand(1).cards(), &vec![Sawmill, Quarry, Brickyard]);
+    }
+
+    #[test]
+    fn update_applies_actions_revealed_before_the_seventh_card() {
+        let public_players = public_players();
+        let mut tracker = CardTracker::new();
+        let history = vec![
+            revealed(5, 0, PublicAction::Build(Barracks)),
+            revealed(5, 1, PublicAction::Build(LumberYard)),
+            revealed(5, 2, PublicAction::Discard),
+        ];
+
+        update(&mut tracker, &public_players, &[], 5, vec![LumberYard, StonePit]);
+        tracker.played(1, &Action::Build(LumberYard, Borrowing::no_borrowing()));
+        update(&mut tracker, &public_players, &history, 5, vec![StonePit]);
+        // Asking again on the same turn doesn't apply the revealed actions twice.
+        update(&mut tracker, &public_players, &history, 5, vec![StonePit]);
+
+        assert_eq!(tracker.hand(0).size(), 1);
+        assert_eq!(tracker.hand(1).cards(), &vec![StonePit]);
+        assert_eq!(tracker.hand(2).size(), 1);
+    }
+
+    #[test]
+    fn sample_hands_deals_hands_consistent_with_what_is_known() {
+        let public_players = public_players();
+        let mut tracker = CardTracker::new();
+        let history = vec![
+            revealed(0, 0, PublicAction::Build(Barracks)),
+            revealed(0, 1, PublicAction::Build(LumberYard)),
+            revealed(0, 2, PublicAction::Discard),
+        ];
+
+        update(
+            &mut tracker,
+            &public_players,
+            &history,
+            0,
+            vec![LumberYard, StonePit, ClayPool],
+        );
+        tracker.played(1, &Action::Build(LumberYard, Borrowing::no_borrowing()));
+        let player = update(&mut tracker, &public_players, &history, 1, vec![Altar, Baths]);
+
+        let visible_game = visible_game(&public_players, &history, 1);
+        let hands = tracker.sample_hands(&player, &visible_game, &mut derive_rng(0, 0));
+        assert_eq!(hands.len(), 3);
+        assert_eq!(hands[1], vec![Altar, Baths]);
+        assert_eq!(sorted(&hands[2]), sorted(&[StonePit, ClayPool]));
+        assert_eq!(hands[0].len(), 2);
+        for card in &hands[0] {
+            assert!(![LumberYard, StonePit, ClayPool, Barracks, Altar, Baths].contains(card));
+            assert_eq!(card.age(), &Age::First);
+        }
+    }
+
+    fn update(
+        tracker: &mut CardTracker,
+        public_players: &[PublicPlayer],
+        history: &[RevealedAction],
+        turn: u32,
+        hand: Vec<Card>,
+    ) -> Player {
+        let player = Player::new_from_public(&public_players[1], hand);
+        tracker.update(&player, &visible_game(public_players, history, turn));
+        player
+    }
+
+    fn visible_game<'a>(
+        public_players: &'a [PublicPlayer],
+        history: &'a [RevealedAction],
+        turn: u32,
+    ) -> VisibleGame<'a> {
+        VisibleGame {
+            public_players,
+            history,
+            player_index: 1,
+            turn,
+        }
+    }
+
+    fn revealed(turn: u32, player_index: usize, action: PublicAction) -> RevealedAction {
+        RevealedAction {
+            turn,
+            player_index,
+            action,
+            payments: vec![],
+            seventh_card: false,
+        }
+    }
+
+    fn sorted(cards: &[Card]) -> Vec<String> {
+        cards.iter().map(|card| card.to_string()).sorted().collect()
+    }
+
+    fn public_players() -> Vec<PublicPlayer> {
+        [
+            WonderType::ColossusOfRhodes,
+            WonderType::LighthouseOfAlexandria,
+            WonderType::TempleOfArtemis,
+        ]
+        .iter()
+        .map(|wonder_type| PublicPlayer::new(&Player::new(*wonder_type, WonderSide::A)))
+        .collect()
+    }
+}
@@ -5,9 +5,12 @@ use std::io::Write;
 
 use crate::action::{Action, ActionOptions, Borrowing};
 use crate::algorithms::PlayingAlgorithm;
+use crate::card::Card;
+use crate::error::GameError;
 use crate::game::VisibleGame;
 use crate::player::Player;
 use crate::table::Table;
+use crate::wonder::WonderBoard;
 use itertools::Itertools;
 
 #[derive(Debug)]
@@ -15,7 +18,7 @@ pub struct Human;
 
 impl Human {
     /// Prints out the current game state for the given user index.
-    fn print_state_for_user(player: &Player, visible_game: &VisibleGame) {
+    fn print_state_for_user(player: &Player, visible_game: &VisibleGame) -> Result<(), GameError> {
         let all_players = visible_game.public_players;
         let player_index = visible_game.player_index;
 
@@ -30,7 +33,7 @@ impl Human {
                 .built_structures
                 .iter()
                 .map(|card| vec![card.to_string(), card.power().to_string()])
-                .for_each(|row| played.add(row));
+                .try_for_each(|row| played.add(row))?;
 
             println!(
                 "Player {}{}",
@@ -43,6 +46,11 @@ impl Human {
                 other_player.wonder.wonder_side,
                 other_player.wonder.starting_resource()
             );
+            println!(
+                "  Wonder stages built: {} of {}",
+                other_player.wonder_stages_built,
+                other_player.wonder.stage_count()
+            );
             println!("  Coins: {}", other_player.coins);
             if !other_player.built_structures.is_empty() {
                 played.print("  ", 4);
@@ -61,7 +69,7 @@ impl Human {
             .iter()
             .enumerate()
             .map(|(i, card)| {
-                let options = player.options_for_card(card, visible_game, false);
+                let options = player.options_for_card(card, visible_game, None);
                 let playability = if !options.possible() {
                     "  "
                 } else if options.own_cards_only() {
@@ -69,74 +77,172 @@ impl Human {
                 } else {
                     "# "
                 };
+                let cost = if player.has_built(card) {
+                    "can't build (already built)".to_string()
+                } else {
+                    match player.chained_from(card) {
+                        Some(chained_from) => format!("free (chained from {})", chained_from),
+                        None => card.cost().to_string(),
+                    }
+                };
                 vec![
                     playability.to_string() + &(i + 1).to_string(),
                     card.to_string(),
-                    card.cost().to_string(),
+                    cost,
                     card.power().to_string(),
                 ]
             })
-            .for_each(|row| hand.add(row));
+            .try_for_each(|row| hand.add(row))?;
 
         println!("Your hand:");
         hand.print("  ", 4);
+        if player.free_build_available() {
+            println!("You can build one structure for free this age.");
+        }
+
+        let mut stages = Table::new(vec![
+            String::from("Stage"),
+            String::from("Cost"),
+            String::from("Power"),
+            String::from("Built"),
+        ]);
+        player
+            .wonder()
+            .stages()
+            .iter()
+            .enumerate()
+            .map(|(i, stage)| {
+                vec![
+                    (i + 1).to_string(),
+                    stage.cost.to_string(),
+                    stage.powers.iter().format(", ").to_string(),
+                    if i < player.built_wonder_stages().len() {
+                        "yes"
+                    } else {
+                        "no"
+                    }
+                    .to_string(),
+                ]
+            })
+            .try_for_each(|row| stages.add(row))?;
 
-        // TODO: show wonder stages and which have been built
-        // TODO: show chained cards in cost column
+        println!();
+        println!("Your wonder stages:");
+        stages.print("  ", 4);
+        Ok(())
+    }
+
+    /// Prints a table of the given cards, numbered from 1, with the given title for the card column.
+    fn print_cards(cards: &[Card], column_title: &str) -> Result<(), GameError> {
+        let mut table = Table::new(vec![
+            String::from("Id"),
+            String::from(column_title),
+            String::from("Power"),
+        ]);
+        cards
+            .iter()
+            .enumerate()
+            .map(|(i, card)| vec![(i + 1).to_string(), card.to_string(), card.power().to_string()])
+            .try_for_each(|row| table.add(row))?;
+        table.print("  ", 4);
+        Ok(())
+    }
+
+    /// Prints a table of the given wonders, numbered from 1.
+    fn print_wonders(wonders: &[WonderBoard]) -> Result<(), GameError> {
+        let mut table = Table::new(vec![
+            String::from("Id"),
+            String::from("Wonder"),
+            String::from("Side"),
+            String::from("Stages"),
+        ]);
+        wonders
+            .iter()
+            .enumerate()
+            .map(|(i, wonder)| {
+                vec![
+                    (i + 1).to_string(),
+                    wonder.name().to_string(),
+                    format!("{:?}", wonder.wonder_side),
+                    wonder
+                        .stages()
+                        .iter()
+                        .map(|stage| stage.powers.iter().format(", "))
+                        .format(" | ")
+                        .to_string(),
+                ]
+            })
+            .try_for_each(|row| table.add(row))?;
+        table.print("  ", 4);
+        Ok(())
+    }
+
+    /// Tells the user if something couldn't be printed. The user is still asked for their choice, so a display problem
+    /// doesn't stop the game.
+    fn report_error(result: Result<(), GameError>) {
+        if let Err(error) = result {
+            println!("Couldn't display the game: {}", error);
+        }
     }
 
     /// Displays the current state of the game to the user (using [`Human::print_state_for_user`]) and then interactively
     /// asks the user for their action.
     fn ask_for_action(player: &Player, visible_game: &VisibleGame) -> Action {
-        // TODO: Support building a wonder stage.
-
         println!();
         println!();
-        Self::print_state_for_user(player, visible_game);
+        Self::report_error(Self::print_state_for_user(player, visible_game));
 
         let hand = player.hand();
 
         let action = loop {
             println!();
             print!("Please enter the id of the card to play: ");
-            let card = *Self::choose_from_slice(&hand);
+            let card = *Self::choose_from_slice(hand);
 
-            print!("And now choose (b) to build or (d) to discard: ");
+            print!("And now choose (b) to build, (w) to build a wonder stage, or (d) to discard: ");
             let action = 'outer: loop {
                 io::stdout().flush().unwrap();
                 let mut choice = String::new();
                 io::stdin().read_line(&mut choice).unwrap();
-                match choice.trim().to_lowercase().as_str() {
-                    "b" => {
-                        let options = player.options_for_card(&card, visible_game, false);
-                        if options.own_cards_only() || !options.possible() {
-                            // Use own cards, or action not possible (which is caught later).
-                            break Action::Build(card, Borrowing::no_borrowing());
-                        } else if options.actions.len() == 1 {
-                            // Borrowing, but only one option, so just do it.
-                            break options.actions[0].clone();
-                        } else {
-                            // Have user select which borrowing option to go with.
-                            println!();
-                            println!("Options for borrowing required resources:");
-                            Self::print_borrowing_options(
-                                &options,
-                                visible_game.left_neighbour_index(),
-                                visible_game.right_neighbour_index(),
-                                &mut io::stdout(),
-                            );
-                            print!("Please enter the id of the borrow you want to make: ");
-                            break 'outer Self::choose_from_slice(&options.actions).clone();
-                        }
-                    }
+                let choice = choice.trim().to_lowercase();
+                let options = match choice.as_str() {
+                    "b" => player.options_for_card(&card, visible_game, None),
+                    "w" => player.options_for_wonder_stage(&card, visible_game, None),
                     "d" => break Action::Discard(card),
-                    _ => {}
+                    _ => {
+                        print!("Please enter either b, w or d: ");
+                        continue;
+                    }
                 };
-                print!("Please enter either b or d: ");
+                if options.own_cards_only() || !options.possible() {
+                    // Use own cards, or action not possible (which is caught later).
+                    break if choice == "w" {
+                        Action::Wonder(card, Borrowing::no_borrowing())
+                    } else {
+                        Action::Build(card, Borrowing::no_borrowing())
+                    };
+                } else if options.actions.len() == 1 && !matches!(options.actions[0], Action::FreeBuild(_)) {
+                    // Borrowing, but only one option, so just do it.
+                    break options.actions[0].clone();
+                } else {
+                    // Have user select which borrowing option to go with.
+                    println!();
+                    println!("Options for affording the card:");
+                    Self::print_borrowing_options(
+                        &options,
+                        visible_game.left_neighbour_index(),
+                        visible_game.right_neighbour_index(),
+                        &mut io::stdout(),
+                    );
+                    print!("Please enter the id of the borrow you want to make: ");
+                    break 'outer Self::choose_from_slice(&options.actions).clone();
+                }
             };
 
             if player.can_play(&action, visible_game) {
                 break action;
+            } else if matches!(action, Action::Build(card, _) if player.has_built(&card)) {
+                println!("You've already built a {}. Please try again", card);
             } else {
                 println!("You can't play that card. Please try again");
             }
@@ -146,6 +252,55 @@ impl Human {
         action
     }
 
+    /// Displays the current state of the game to the user and then asks them which of the given cards from the
+    /// discard pile they want to build for free.
+    fn ask_for_discard_build(player: &Player, visible_game: &VisibleGame, cards: &[Card]) -> Card {
+        println!();
+        println!();
+        Self::report_error(Self::print_state_for_user(player, visible_game));
+
+        println!();
+        println!("You may build a card from the discard pile for free:");
+        Self::report_error(Self::print_cards(cards, "Card"));
+
+        println!();
+        print!("Please enter the id of the card to build: ");
+        let card = *Self::choose_from_slice(cards);
+        println!();
+        card
+    }
+
+    /// Displays the current state of the game to the user and then asks them which of the given neighbours' guilds they
+    /// want to copy during final scoring.
+    fn ask_for_guild_to_copy(player: &Player, visible_game: &VisibleGame, guilds: &[Card]) -> Card {
+        println!();
+        println!();
+        Self::report_error(Self::print_state_for_user(player, visible_game));
+
+        println!();
+        println!("You may copy one of your neighbours' guilds:");
+        Self::report_error(Self::print_cards(guilds, "Guild"));
+
+        println!();
+        print!("Please enter the id of the guild to copy: ");
+        let guild = *Self::choose_from_slice(guilds);
+        println!();
+        guild
+    }
+
+    /// Asks the user which of the given wonders they want to play with.
+    fn ask_for_wonder(wonders: &[WonderBoard]) -> WonderBoard {
+        println!();
+        println!("Available wonders:");
+        Self::report_error(Self::print_wonders(wonders));
+
+        println!();
+        print!("Please enter the id of the wonder to play with: ");
+        let wonder = *Self::choose_from_slice(wonders);
+        println!();
+        wonder
+    }
+
     /// Asks the user to choose one of the items in the given slice.
     fn choose_from_slice<T>(slice: &[T]) -> &T {
         loop {
@@ -168,7 +323,9 @@ impl Human {
         out: &mut W,
     ) {
         for (index, option) in options.actions.iter().enumerate() {
-            if let Action::Build(_, borrowing) = option {
+            if let Action::FreeBuild(_) = option {
+                writeln!(out, "   {}) Use your free build for this age", index + 1).unwrap();
+            } else if let Action::Build(_, borrowing) | Action::Wonder(_, borrowing) = option {
                 let mut borrows = vec![];
                 if !borrowing.left.is_empty() {
                     borrows.push(format!(
@@ -191,16 +348,31 @@ impl Human {
 }
 
 impl PlayingAlgorithm for Human {
+    fn name(&self) -> &'static str {
+        "Human"
+    }
+
     fn get_next_action(&mut self, player: &Player, visible_game: &VisibleGame) -> Action {
         Self::ask_for_action(player, visible_game)
     }
+
+    fn get_discard_build(&mut self, player: &Player, visible_game: &VisibleGame, cards: &[Card]) -> Option<Card> {
+        Some(Self::ask_for_discard_build(player, visible_game, cards))
+    }
+
+    fn get_guild_to_copy(&mut self, player: &Player, visible_game: &VisibleGame, guilds: &[Card]) -> Option<Card> {
+        Some(Self::ask_for_guild_to_copy(player, visible_game, guilds))
+    }
+
+    fn choose_wonder(&mut self, wonders: &[WonderBoard]) -> WonderBoard {
+        Self::ask_for_wonder(wonders)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::action::Borrow;
-    use crate::card::Card;
     use crate::resources::Resource;
 
     #[test]
@@ -254,6 +426,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn print_borrowing_options_with_free_build() {
+        let mut out: Vec<u8> = Vec::new();
+        let actions = vec![
+            Action::Build(
+                Card::Baths,
+                Borrowing::new(vec![Borrow::new(Card::StonePit, Resource::Stone)], vec![]),
+            ),
+            Action::FreeBuild(Card::Baths),
+        ];
+        Human::print_borrowing_options(&ActionOptions { actions }, 2, 0, &mut out);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "   1) Borrow Stone Pit from player 3\n   2) Use your free build for this age\n"
+        );
+    }
+
     #[test]
     fn print_borrowing_options_with_two_options() {
         let mut out: Vec<u8> = Vec::new();
@@ -2,18 +2,100 @@
 
 use std::fmt::Debug;
 
-use crate::action::Action;
+use crate::action::{Action, IllegalActionReason};
+use crate::algorithms::human::Human;
+use crate::algorithms::monte_carlo::MonteCarlo;
+use crate::algorithms::random::Random;
+use crate::card::Card;
 use crate::game::VisibleGame;
 use crate::player::Player;
+use crate::wonder::WonderBoard;
+use rand_chacha::ChaCha8Rng;
 
+pub mod card_tracker;
 pub mod human;
 pub mod monte_carlo;
 pub mod random;
 
 /// An algorithm that can play 7 Wonders.
 pub trait PlayingAlgorithm: Debug {
+    /// Returns the name of this algorithm, used to reattach the algorithm when a saved game is loaded (see
+    /// [`by_name`]).
+    fn name(&self) -> &'static str;
+
     /// Returns the action that should be performed by the given player.
     ///
     /// `visible_game` is a restricted view of the state of all players in the game.
+    ///
+    /// On the last turn of an age, this is called a second time for a player able to play the seventh card of the age
+    /// (ie. on the Hanging Gardens of Babylon), when their hand contains just that final card.
     fn get_next_action(&mut self, player: &Player, visible_game: &VisibleGame) -> Action;
+
+    /// Returns the card the given player should build for free from the discard pile, or `None` to build nothing. Only
+    /// called at the end of a turn in which the player built a wonder stage that allows this (ie. on the Mausoleum of
+    /// Halicarnassus).
+    ///
+    /// `cards` contains the cards in the discard pile that the player is allowed to build, and is never empty. By
+    /// default, the first card offered is built.
+    fn get_discard_build(&mut self, _player: &Player, _visible_game: &VisibleGame, cards: &[Card]) -> Option<Card> {
+        cards.first().copied()
+    }
+
+    /// Called, with the reason it was illegal, when the action returned by [`PlayingAlgorithm::get_next_action`] was
+    /// illegal and the game is going to ask for another (see [`crate::game::IllegalActionPolicy::Retry`]). Does nothing
+    /// by default.
+    fn illegal_action(&mut self, _action: &Action, _reason: IllegalActionReason) {}
+
+    /// Replaces the random number generator used by this algorithm for any random choices it makes. Called when a game
+    /// is created, with a generator derived from the game's seed, so that games can be reproduced exactly. Algorithms
+    /// can therefore be created with any generator, such as one seeded from entropy, before being passed to a game.
+    /// Does nothing by default.
+    fn reseed(&mut self, _rng: ChaCha8Rng) {}
+
+    /// Returns how far this algorithm's random number generator has got through its stream of numbers (see
+    /// [`ChaCha8Rng::get_word_pos`]), so that a saved game can restore it and the algorithm's random choices carry on
+    /// as they would have done (see [`crate::game::Game::restore`]). `None` by default, for algorithms that make no
+    /// random choices.
+    fn rng_position(&self) -> Option<u128> {
+        None
+    }
+
+    /// Returns the wonder, chosen from `wonders`, this algorithm wants to play with. Only called when drafting wonders
+    /// at the start of a game. By default, the first wonder offered is chosen.
+    fn choose_wonder(&mut self, wonders: &[WonderBoard]) -> WonderBoard {
+        wonders[0]
+    }
+
+    /// Returns the neighbour's guild the given player should copy during final scoring, or `None` to copy nothing. Only
+    /// called for a player who built a wonder stage that allows this (ie. on the Statue of Zeus in Olympia, side B).
+    ///
+    /// `guilds` contains the guilds the player is allowed to copy, and is never empty. By default, the guild giving the
+    /// player the highest score is chosen.
+    fn get_guild_to_copy(&mut self, player: &Player, visible_game: &VisibleGame, guilds: &[Card]) -> Option<Card> {
+        player.best_guild_to_copy(guilds, visible_game.left_neighbour(), visible_game.right_neighbour())
+    }
+}
+
+/// Creates the algorithm with the given name (see [`PlayingAlgorithm::name`]), or returns `None` if there's no such
+/// algorithm. Algorithms only used internally by other algorithms can't be created by name.
+pub fn by_name(name: &str) -> Option<Box<dyn PlayingAlgorithm>> {
+    match name {
+        "Human" => Some(Box::new(Human {})),
+        "MonteCarlo" => Some(Box::new(MonteCarlo::default())),
+        "Random" => Some(Box::new(Random::default())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn by_name_creates_algorithm_with_that_name() {
+        for name in &["Human", "MonteCarlo", "Random"] {
+            assert_eq!(*name, by_name(name).unwrap().name());
+        }
+        assert!(by_name("Unknown").is_none());
+    }
 }
@@ -1,51 +1,82 @@
 //! A computer algorithm for playing 7 Wonders. Uses Monte Carlo tree search to determine which action to take.
 
 use crate::action::Action;
+use crate::algorithms::card_tracker::CardTracker;
 use crate::algorithms::random::Random;
 use crate::algorithms::{random, PlayingAlgorithm};
-use crate::card;
 use crate::card::Card;
-use crate::game::{Game, OutputMode, SentientPlayer, VisibleGame};
+use crate::game::{Game, GameState, VisibleGame};
 use crate::player::Player;
-use std::collections::HashMap;
+use crate::utils::derive_rng;
+use crate::wonder::WonderBoard;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
 
 #[derive(Debug)]
-pub struct MonteCarlo;
+pub struct MonteCarlo {
+    rng: ChaCha8Rng,
+    card_tracker: CardTracker,
+}
+
+impl MonteCarlo {
+    /// Creates an algorithm making its random choices, including those made while simulating games, using the given
+    /// random number generator.
+    pub fn new(rng: ChaCha8Rng) -> MonteCarlo {
+        MonteCarlo {
+            rng,
+            card_tracker: CardTracker::new(),
+        }
+    }
+}
+
+impl Default for MonteCarlo {
+    /// Creates an algorithm seeded from entropy, to be reseeded by its game (see [`PlayingAlgorithm::reseed`]).
+    fn default() -> MonteCarlo {
+        MonteCarlo::new(derive_rng(rand::random(), 0))
+    }
+}
 
 impl PlayingAlgorithm for MonteCarlo {
+    fn name(&self) -> &'static str {
+        "MonteCarlo"
+    }
+
     fn get_next_action(&mut self, player: &Player, visible_game: &VisibleGame) -> Action {
         // TODO: this isn't classic Monte Carlo tree search yet. We just evaluate each possible immediate next action
         //  and then play the game randomly until the end as many times as possible, then pick the action that won most.
         //  No tree is actually built, and there's no expansion/exploration tradeoffs.
 
+        // If the game is over there's nothing to simulate (and we shouldn't have been asked for an action anyway).
+        if visible_game.age().is_err() {
+            return random::get_next_action(player, visible_game, &mut self.rng);
+        }
+
         // Build a vector of possible actions. We'll evaluate the strength of each and pick the best.
         let mut action_options = Vec::new();
         for card in player.hand() {
-            let mut options = player.options_for_card(card, visible_game, false);
-            if options.possible() {
-                // TODO: for now, just take one option. This will be the only option if the card can be played without
-                //  borrowing; otherwise in many cases it will be the one-and-only borrow option. Sometimes, though,
-                //  there can be tens of ways to borrow the required resources, which increases our search space
-                //  greatly, so just pick one. Long term, we should search all of them, but collapse borrowing that
-                //  results in the same coin transfers, as these are equivalent in terms of the strength of the action.
-                action_options.push(options.actions.swap_remove(0));
+            for mut options in [
+                player.options_for_card(card, visible_game, None),
+                player.options_for_wonder_stage(card, visible_game, None),
+            ] {
+                if options.possible() {
+                    // TODO: for now, just take one option. This will be the only option if the card can be played
+                    //  without borrowing; otherwise in many cases it will be the one-and-only borrow option. Sometimes,
+                    //  though, there can be tens of ways to borrow the required resources, which increases our search
+                    //  space greatly, so just pick one. Long term, we should search all of them, but collapse borrowing
+                    //  that results in the same coin transfers, as these are equivalent in terms of the strength of
+                    //  the action.
+                    action_options.push(options.actions.swap_remove(0));
+                }
             }
             action_options.push(Action::Discard(*card));
         }
 
-        // Cards we know about: those in our hand, and those played by ourselves and the other players. We'll invent
-        // random hands for the other players based on the remaining cards valid for the given number of players.
-        // TODO: we can do better here. When we pass our hand to our neighbour, we know the cards they have. We should
-        //  write a separate piece of code (that can be reused by multiple algorithms) that tracks this information.
-        let mut known_cards: HashMap<Card, u32> = HashMap::new();
-        for card in player.hand() {
-            *known_cards.entry(*card).or_insert(0) += 1;
-        }
-        for public_player in visible_game.public_players {
-            for card in &public_player.built_structures {
-                *known_cards.entry(*card).or_insert(0) += 1;
-            }
-        }
+        // Track what we know of the other players' hands. Each simulated game deals them hands consistent with this.
+        self.card_tracker.update(player, visible_game);
+
+        // If everyone's actions this turn have already been revealed, we're playing the seventh card of the age.
+        let seventh_card = visible_game.history_for_turn(visible_game.turn).next().is_some();
 
         // Try 10 full games for each possible action, and choose the action where we win the most.
         // TODO: we need to run way more than 10 games! But everything is far too slow at the moment. Need to optimise
@@ -53,71 +84,132 @@ impl PlayingAlgorithm for MonteCarlo {
         //  about their action, giving us more time. Also, we should obviously eventually multi-thread this.
         let mut strength = vec![0; action_options.len()];
         for _ in 0..10 {
-            for (option_index, action) in action_options.iter().enumerate() {
-                let mut deck = card::new_deck_without(
-                    &visible_game.age(),
-                    visible_game.public_players.len() as u32,
-                    &known_cards,
-                );
-                let mut sentient_players: Vec<SentientPlayer> = Vec::with_capacity(visible_game.public_players.len());
-                for (i, public_player) in visible_game.public_players.iter().enumerate() {
+            // Each simulated game is seeded from our own random number generator, so our choices are reproducible.
+            let seed = self.rng.gen();
+
+            // Recreate the current position from what we can see. We know our own player in full. Everyone else gets a
+            // hand consistent with what we know of their actual hand.
+            let hands = self.card_tracker.sample_hands(player, visible_game, &mut self.rng);
+            let players = visible_game
+                .public_players
+                .iter()
+                .zip(hands)
+                .enumerate()
+                .map(|(i, (public_player, hand))| {
                     if i == visible_game.player_index {
-                        // Us. Use our hand and an algorithm that will play the chosen card followed by random cards
-                        // thereafter.
-                        sentient_players.push(SentientPlayer {
-                            player: Player::new_from_public(&public_player, player.hand().clone()),
-                            algorithm: Box::new(MonteCarloAlg::new(action.clone())),
-                        });
+                        player.clone()
                     } else {
-                        // Everyone else. Deal a random hand (since we don't know their actual hand) and play randomly
-                        // throughout.
-                        sentient_players.push(SentientPlayer {
-                            player: Player::new_from_public(
-                                &public_player,
-                                deck.drain(0..player.hand().len()).collect(),
-                            ),
-                            algorithm: Box::new(Random {}),
-                        });
+                        Player::new_from_public(public_player, hand)
+                    }
+                })
+                .collect();
+            let state = GameState::new(players, visible_game.turn, vec![], visible_game.history.to_vec(), seed)
+                .and_then(|mut state| {
+                    if seventh_card {
+                        state.skip_to_seventh_card()?;
                     }
+                    Ok(state)
+                });
+            let state = match state {
+                Ok(state) => state,
+                // We can't simulate from this position, so there's no evidence to choose an action on. Play randomly.
+                Err(_) => {
+                    let action = random::get_next_action(player, visible_game, &mut self.rng);
+                    self.card_tracker.played(visible_game.player_index, &action);
+                    return action;
                 }
+            };
+
+            // Fork the position for each possible action. We play the chosen action followed by random actions
+            // thereafter, and everyone else plays randomly throughout.
+            for (option_index, action) in action_options.iter().enumerate() {
+                let algorithms = (0..visible_game.public_players.len())
+                    .map(|i| -> Box<dyn PlayingAlgorithm> {
+                        if i == visible_game.player_index {
+                            Box::new(MonteCarloAlg::new(action.clone(), Game::algorithm_rng(seed, i)))
+                        } else {
+                            Box::new(Random::new(Game::algorithm_rng(seed, i)))
+                        }
+                    })
+                    .collect();
 
                 // Play the game to the end and increment the strength of this action if we win.
-                let mut game = Game::new_with_players(sentient_players, visible_game.turn, OutputMode::NoOutput);
-                let scores = game.play();
-                if scores.iter().enumerate().max_by_key(|(_, score)| *score).unwrap().0 == visible_game.player_index {
+                let result = Game::new_with_state(state.clone(), algorithms).and_then(|mut game| game.play());
+                if matches!(result, Ok(result) if result.is_winner(visible_game.player_index)) {
                     strength[option_index] += 1;
                 }
             }
         }
 
-        action_options
+        let action = action_options
             .iter()
             .zip(strength.iter())
             .max_by_key(|(_, strength)| *strength)
             .unwrap()
             .0
-            .clone()
+            .clone();
+        self.card_tracker.played(visible_game.player_index, &action);
+        action
+    }
+
+    fn get_discard_build(&mut self, player: &Player, visible_game: &VisibleGame, cards: &[Card]) -> Option<Card> {
+        // TODO: simulate games as we do for get_next_action. For now, greedily build the card that increases our score
+        //  the most.
+        cards
+            .iter()
+            .max_by_key(|card| {
+                let mut player = player.clone();
+                player.build_from_discard(card, visible_game);
+                player
+                    .score(visible_game.left_neighbour(), visible_game.right_neighbour())
+                    .total()
+            })
+            .copied()
+    }
+
+    fn reseed(&mut self, rng: ChaCha8Rng) {
+        self.rng = rng;
+    }
+
+    fn rng_position(&self) -> Option<u128> {
+        Some(self.rng.get_word_pos())
+    }
+
+    fn choose_wonder(&mut self, wonders: &[WonderBoard]) -> WonderBoard {
+        *wonders.choose(&mut self.rng).unwrap()
     }
 }
 
 #[derive(Debug)]
 struct MonteCarloAlg {
     action: Option<Action>,
+    rng: ChaCha8Rng,
 }
 
 impl MonteCarloAlg {
-    fn new(action: Action) -> MonteCarloAlg {
-        MonteCarloAlg { action: Some(action) }
+    fn new(action: Action, rng: ChaCha8Rng) -> MonteCarloAlg {
+        MonteCarloAlg {
+            action: Some(action),
+            rng,
+        }
     }
 }
 
 impl PlayingAlgorithm for MonteCarloAlg {
+    fn name(&self) -> &'static str {
+        "MonteCarloAlg"
+    }
+
     fn get_next_action(&mut self, player: &Player, visible_game: &VisibleGame) -> Action {
         if let Some(action) = self.action.take() {
             if player.can_play(&action, visible_game) {
                 return action;
             }
         }
-        random::get_next_action(player, visible_game)
+        random::get_next_action(player, visible_game, &mut self.rng)
+    }
+
+    fn get_discard_build(&mut self, _player: &Player, _visible_game: &VisibleGame, cards: &[Card]) -> Option<Card> {
+        random::get_discard_build(cards, &mut self.rng)
     }
 }
@@ -2,33 +2,80 @@
 //! a card if neither is possible.
 
 use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
 
 use crate::action::Action;
 use crate::algorithms::PlayingAlgorithm;
+use crate::card::Card;
 use crate::game::VisibleGame;
 use crate::player::Player;
+use crate::utils::derive_rng;
+use crate::wonder::WonderBoard;
 
 #[derive(Debug)]
-pub struct Random;
+pub struct Random {
+    rng: ChaCha8Rng,
+}
+
+impl Random {
+    /// Creates an algorithm making its random choices using the given random number generator.
+    pub fn new(rng: ChaCha8Rng) -> Random {
+        Random { rng }
+    }
+}
+
+impl Default for Random {
+    /// Creates an algorithm seeded from entropy, to be reseeded by its game (see [`PlayingAlgorithm::reseed`]).
+    fn default() -> Random {
+        Random::new(derive_rng(random(), 0))
+    }
+}
 
 impl PlayingAlgorithm for Random {
+    fn name(&self) -> &'static str {
+        "Random"
+    }
+
     fn get_next_action(&mut self, player: &Player, visible_game: &VisibleGame) -> Action {
-        get_next_action(player, visible_game)
+        get_next_action(player, visible_game, &mut self.rng)
+    }
+
+    fn get_discard_build(&mut self, _player: &Player, _visible_game: &VisibleGame, cards: &[Card]) -> Option<Card> {
+        get_discard_build(cards, &mut self.rng)
+    }
+
+    fn reseed(&mut self, rng: ChaCha8Rng) {
+        self.rng = rng;
+    }
+
+    fn rng_position(&self) -> Option<u128> {
+        Some(self.rng.get_word_pos())
+    }
+
+    fn choose_wonder(&mut self, wonders: &[WonderBoard]) -> WonderBoard {
+        *wonders.choose(&mut self.rng).unwrap()
     }
 }
 
-pub fn get_next_action(player: &Player, visible_game: &VisibleGame) -> Action {
-    let action_to_take = player
-        .hand()
-        .iter()
-        .map(|card| player.options_for_card(card, visible_game, true))
+pub fn get_next_action(player: &Player, visible_game: &VisibleGame, rng: &mut ChaCha8Rng) -> Action {
+    let mut options = vec![];
+    for card in player.hand() {
+        options.push(player.options_for_card(card, visible_game, Some(&mut *rng)));
+        options.push(player.options_for_wonder_stage(card, visible_game, Some(&mut *rng)));
+    }
+    let action_to_take = options
+        .into_iter()
         .filter(|actions| actions.possible())
         .map(|mut actions| actions.actions.swap_remove(0))
-        .choose(&mut thread_rng());
+        .choose(rng);
 
     match action_to_take {
         Some(action) => action,
-        None => Action::Discard(*player.hand().iter().choose(&mut thread_rng()).unwrap()),
+        None => Action::Discard(*player.hand().iter().choose(rng).unwrap()),
     }
-    // TODO: also randomly choose to build a Wonder stage, when doing so is supported.
+}
+
+/// Randomly picks one of the given cards to build from the discard pile.
+pub fn get_discard_build(cards: &[Card], rng: &mut ChaCha8Rng) -> Option<Card> {
+    cards.choose(rng).copied()
 }
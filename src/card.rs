@@ -2,9 +2,9 @@ use std::fmt;
 use std::fmt::{Display, Formatter};
 
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand_chacha::ChaCha8Rng;
 use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
+use strum_macros::{EnumIter, EnumString};
 
 use crate::power::Power;
 use crate::power::{CountableGameItem, ScienceItem};
@@ -14,7 +14,7 @@ use crate::resources::Resource;
 use lazy_static::lazy_static;
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, EnumIter)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, EnumIter, EnumString)]
 #[allow(dead_code)]
 pub enum Card {
     // Age 1
@@ -139,13 +139,24 @@ pub enum Card {
     BuildersGuild,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Age {
     First,
     Second,
     Third,
 }
 
+impl Age {
+    /// Returns the value of the victory token awarded for winning a military conflict at the end of this age.
+    pub fn victory_token_value(&self) -> i32 {
+        match self {
+            Age::First => 1,
+            Age::Second => 3,
+            Age::Third => 5,
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Hash)]
 pub enum Colour {
     Brown,
@@ -1099,7 +1110,7 @@ lazy_static! {
 
 #[allow(dead_code)]
 impl Card {
-    fn info(&self) -> &CardInfo {
+    fn info(&self) -> &CardInfo<'_> {
         match self {
             Card::LumberYard => &LUMBER_YARD,
             Card::StonePit => &STONE_PIT,
@@ -1182,6 +1193,12 @@ impl Card {
         }
     }
 
+    /// Returns the name of the card. Note that some different cards share the same name (for example, the first and
+    /// second age looms), in which case they count as the same structure.
+    pub fn name(&self) -> &str {
+        self.info().name
+    }
+
     pub fn age(&self) -> &Age {
         &self.info().age
     }
@@ -1217,13 +1234,13 @@ impl Card {
 
 impl Display for Card {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.info().name)
+        write!(f, "{}", self.name())
     }
 }
 
-/// Creates a new, shuffled deck for the given age and number of players.
-pub fn new_deck(age: &Age, player_count: u32) -> Vec<Card> {
-    new_deck_without(age, player_count, &HashMap::new())
+/// Creates a new deck for the given age and number of players, shuffled using the given random number generator.
+pub fn new_deck(age: &Age, player_count: u32, rng: &mut ChaCha8Rng) -> Vec<Card> {
+    new_deck_without(age, player_count, &HashMap::new(), rng)
 }
 
 /// Creates a new, shuffled deck for the given age, with the cards in `missing` excluded. `missing` is a hash map from
@@ -1231,7 +1248,7 @@ pub fn new_deck(age: &Age, player_count: u32) -> Vec<Card> {
 /// want to allocate random cards to players (because they don't know the actual cards those players have in their
 /// hands), but they know certain cards are definitely not part of those players hands, because they're in the
 /// algorithm's hand or on the table.
-pub fn new_deck_without(age: &Age, player_count: u32, missing: &HashMap<Card, u32>) -> Vec<Card> {
+pub fn new_deck_without(age: &Age, player_count: u32, missing: &HashMap<Card, u32>, rng: &mut ChaCha8Rng) -> Vec<Card> {
     let mut deck: Vec<Card> = vec![];
     let mut guilds: Vec<Card> = vec![];
 
@@ -1255,53 +1272,70 @@ pub fn new_deck_without(age: &Age, player_count: u32, missing: &HashMap<Card, u3
 
     // Shuffle the guilds separately and add player_count + 2 random ones to the deck.
     if *age == Age::Third {
-        guilds.shuffle(&mut thread_rng());
+        guilds.shuffle(rng);
         for _ in 0..guild_count {
             deck.push(guilds.pop().unwrap());
         }
     }
 
     // Shuffle the complete deck and return it.
-    deck.shuffle(&mut thread_rng());
+    deck.shuffle(rng);
     deck
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::derive_rng;
     use std::iter::FromIterator;
 
     #[test]
     fn new_deck_has_right_number_of_cards() {
-        assert_eq!(21, new_deck(&Age::First, 3).len());
-        assert_eq!(28, new_deck(&Age::First, 4).len());
-        assert_eq!(35, new_deck(&Age::First, 5).len());
-        assert_eq!(42, new_deck(&Age::First, 6).len());
-        assert_eq!(49, new_deck(&Age::First, 7).len());
+        assert_eq!(21, new_deck(&Age::First, 3, &mut rng()).len());
+        assert_eq!(28, new_deck(&Age::First, 4, &mut rng()).len());
+        assert_eq!(35, new_deck(&Age::First, 5, &mut rng()).len());
+        assert_eq!(42, new_deck(&Age::First, 6, &mut rng()).len());
+        assert_eq!(49, new_deck(&Age::First, 7, &mut rng()).len());
 
-        assert_eq!(21, new_deck(&Age::Second, 3).len());
-        assert_eq!(28, new_deck(&Age::Second, 4).len());
-        assert_eq!(35, new_deck(&Age::Second, 5).len());
-        assert_eq!(42, new_deck(&Age::Second, 6).len());
-        assert_eq!(49, new_deck(&Age::Second, 7).len());
+        assert_eq!(21, new_deck(&Age::Second, 3, &mut rng()).len());
+        assert_eq!(28, new_deck(&Age::Second, 4, &mut rng()).len());
+        assert_eq!(35, new_deck(&Age::Second, 5, &mut rng()).len());
+        assert_eq!(42, new_deck(&Age::Second, 6, &mut rng()).len());
+        assert_eq!(49, new_deck(&Age::Second, 7, &mut rng()).len());
 
-        assert_eq!(21, new_deck(&Age::Third, 3).len());
-        assert_eq!(28, new_deck(&Age::Third, 4).len());
-        assert_eq!(35, new_deck(&Age::Third, 5).len());
-        assert_eq!(42, new_deck(&Age::Third, 6).len());
-        assert_eq!(49, new_deck(&Age::Third, 7).len());
+        assert_eq!(21, new_deck(&Age::Third, 3, &mut rng()).len());
+        assert_eq!(28, new_deck(&Age::Third, 4, &mut rng()).len());
+        assert_eq!(35, new_deck(&Age::Third, 5, &mut rng()).len());
+        assert_eq!(42, new_deck(&Age::Third, 6, &mut rng()).len());
+        assert_eq!(49, new_deck(&Age::Third, 7, &mut rng()).len());
     }
 
     #[test]
     fn no_second_or_third_age_cards_in_first_age_deck() {
-        assert!(!new_deck(&Age::First, 3).contains(&Card::Sawmill));
-        assert!(!new_deck(&Age::First, 3).contains(&Card::Pantheon));
+        assert!(!new_deck(&Age::First, 3, &mut rng()).contains(&Card::Sawmill));
+        assert!(!new_deck(&Age::First, 3, &mut rng()).contains(&Card::Pantheon));
     }
 
     #[test]
     fn new_deck_without_excludes_given_cards() {
-        let deck = new_deck_without(&Age::First, 7, &HashMap::from_iter(vec![(Card::Tavern, 2)]));
+        let deck = new_deck_without(&Age::First, 7, &HashMap::from_iter(vec![(Card::Tavern, 2)]), &mut rng());
         assert_eq!(49 - 2, deck.len());
         assert_eq!(1, deck.iter().filter(|card| **card == Card::Tavern).count());
     }
+
+    #[test]
+    fn new_deck_is_shuffled_the_same_for_the_same_seed() {
+        assert_eq!(
+            new_deck(&Age::Third, 5, &mut derive_rng(42, 0)),
+            new_deck(&Age::Third, 5, &mut derive_rng(42, 0))
+        );
+        assert_ne!(
+            new_deck(&Age::Third, 5, &mut derive_rng(42, 0)),
+            new_deck(&Age::Third, 5, &mut derive_rng(42, 1))
+        );
+    }
+
+    fn rng() -> ChaCha8Rng {
+        derive_rng(0, 0)
+    }
 }
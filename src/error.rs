@@ -0,0 +1,103 @@
+//! Errors that can occur when setting up or playing a game.
+
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use crate::card::Card;
+use crate::game::IllegalAction;
+use crate::wonder::{WonderBoard, WonderType};
+
+/// An error setting up or playing a game.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum GameError {
+    /// A game was set up with fewer than three players.
+    TooFewPlayers,
+    /// A game was set up with more than seven players.
+    TooManyPlayers,
+    /// A turn outside of the 18 turns of the game (0 to 17 inclusive).
+    InvalidTurn(u32),
+    /// A game was set up with a different number of playing algorithms than players.
+    WrongNumberOfAlgorithms { players: usize, algorithms: usize },
+    /// Explicitly allocated wonders didn't include exactly one wonder for each player.
+    WrongNumberOfWonders { players: usize, wonders: usize },
+    /// The same wonder was allocated to more than one player.
+    DuplicateWonder(WonderType),
+    /// A playing algorithm chose a wonder that wasn't available when drafting wonders.
+    UnavailableWonder(WonderBoard),
+    /// A wonder stage position that doesn't exist on the given wonder board.
+    InvalidWonderStage(WonderBoard, u32),
+    /// A card was expected to be in a player's hand, but wasn't.
+    CardNotInHand(Card),
+    /// A row added to a table didn't have the same number of columns as the table.
+    WrongNumberOfColumns { expected: usize, actual: usize },
+    /// A playing algorithm returned an illegal action.
+    IllegalAction(IllegalAction),
+    /// A game state was asked to move on by a decision it isn't waiting for (see [`crate::game::Step`]).
+    UnexpectedStep,
+    /// A game state was given a different number of actions than the number of players waiting to act.
+    WrongNumberOfActions { expected: usize, actual: usize },
+    /// A saved game couldn't be loaded because the given line (counting from 1) is invalid.
+    InvalidSave { line: usize, message: String },
+    /// A game was saved other than between turns, such as once it was over (see [`crate::save::save`]).
+    NotBetweenTurns,
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::TooFewPlayers => write!(f, "Must have at least three players"),
+            GameError::TooManyPlayers => write!(f, "Must have at most seven players"),
+            GameError::InvalidTurn(turn) => write!(f, "Unknown turn {}", turn),
+            GameError::WrongNumberOfAlgorithms { players, algorithms } => write!(
+                f,
+                "Must specify an algorithm for each player ({} algorithms for {} players)",
+                algorithms, players
+            ),
+            GameError::WrongNumberOfWonders { players, wonders } => write!(
+                f,
+                "Must specify a wonder for each player ({} wonders for {} players)",
+                wonders, players
+            ),
+            GameError::DuplicateWonder(wonder_type) => {
+                write!(
+                    f,
+                    "Each player must have a different wonder ({} is repeated)",
+                    wonder_type.name()
+                )
+            }
+            GameError::UnavailableWonder(wonder) => {
+                write!(
+                    f,
+                    "Chosen wonder is not available ({} side {:?})",
+                    wonder.name(),
+                    wonder.wonder_side
+                )
+            }
+            GameError::InvalidWonderStage(wonder, position) => {
+                write!(
+                    f,
+                    "{} side {:?} has no stage {}",
+                    wonder.name(),
+                    wonder.wonder_side,
+                    position + 1
+                )
+            }
+            GameError::CardNotInHand(card) => write!(f, "{} is not in the player's hand", card),
+            GameError::WrongNumberOfColumns { expected, actual } => write!(
+                f,
+                "Row has wrong number of columns (expected {}, got {})",
+                expected, actual
+            ),
+            GameError::IllegalAction(illegal_action) => write!(f, "{}", illegal_action),
+            GameError::UnexpectedStep => write!(f, "The game is not waiting for that decision"),
+            GameError::WrongNumberOfActions { expected, actual } => {
+                write!(f, "Wrong number of actions (expected {}, got {})", expected, actual)
+            }
+            GameError::InvalidSave { line, message } => write!(f, "Invalid saved game on line {}: {}", line, message),
+            GameError::NotBetweenTurns => write!(f, "A game can only be saved between turns"),
+        }
+    }
+}
+
+impl Error for GameError {}
@@ -1,216 +1,940 @@
 //! Represents the whole game state.
 
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::mem;
+
+use itertools::Itertools;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
 use strum::IntoEnumIterator;
 
-use crate::action::Action;
+use crate::action::{Action, CoinTransfer, IllegalActionReason, PublicAction, RevealedAction};
 use crate::algorithms::PlayingAlgorithm;
 use crate::card;
 use crate::card::{Age, Card};
+use crate::error::GameError;
+use crate::observer::{GameEvent, GameObserver};
 use crate::player::{Player, PublicPlayer};
-use crate::wonder::{WonderSide, WonderType};
+use crate::power::Power;
+use crate::score::ScoreBreakdown;
+use crate::utils::derive_rng;
+use crate::wonder::{WonderBoard, WonderSide, WonderType};
 
-/// Represents the whole game state.
+/// A game being played: the [`GameState`], and the algorithms making each player's decisions.
 #[derive(Debug)]
 pub struct Game {
-    /// The players in the game. Moving through the vector starting from index 0 is equivalent to moving clockwise
-    /// around the table of players. The player at the end of the vector also sits next to player at index 0, of course.
-    /// For example, for a game of 5 players, the player at index 1 sits to the left (ie. clockwise) of the player at
-    /// index 0, and the player at index 4 sits to the right (ie. anti-clockwise) of the player at index 0.
-    sentient_players: Vec<SentientPlayer>,
+    /// The state of the game, moved on by the decisions of the algorithms.
+    state: GameState,
 
-    /// The game turn. Runs from 0 to 17 for 3 ages of 6 turns each.
-    turn: u32,
+    /// The algorithm playing for each player, in the same order as the players in `state`. Algorithms live outside
+    /// the state because they maintain their own state and therefore must be mutable when called, while also needing
+    /// access to the game state in order to make decisions.
+    algorithms: Vec<Box<dyn PlayingAlgorithm>>,
 
-    /// The discard pile. Starts empty and gains the final, unplayed card from each player at the end of each age.
-    discard_pile: Vec<Card>,
+    /// The observers told about each event as the game is played (see [`Game::add_observer`]).
+    observers: Vec<Box<dyn GameObserver>>,
 
-    /// Whether to write output while playing the game. Some playing algorithms run "virtual" games as part of their
-    /// calculations and we don't want to write information about those, hence this field.
-    output_mode: OutputMode,
+    /// What to do when a playing algorithm returns an illegal action.
+    illegal_action_policy: IllegalActionPolicy,
 }
 
+/// The stream of random numbers derived from a game's seed (see [`derive_rng`]) used to allocate wonders.
+const SETUP_STREAM: u64 = 0;
+/// The stream used to shuffle the deck for the first age. The decks for the second and third ages use the following
+/// streams.
+const DEAL_STREAM: u64 = 1;
+/// The stream used by the algorithm for the first player. The algorithms for the other players use the following
+/// streams.
+const ALGORITHM_STREAM: u64 = 4;
+
 #[allow(dead_code)]
 impl Game {
     /// Generates a new game with each player playing according to the given algorithm. Players will be randomly
-    /// allocated wonders and dealt a random hand of first age cards. `algorithms` must have between 3 and 7 entries
-    /// inclusive, corresponding to between 3 and 7 players.
-    /// TODO: for now, everyone gets the A side of the wonder.
-    pub fn new(algorithms: Vec<Box<dyn PlayingAlgorithm>>) -> Game {
-        // We have to check this here as well as in new_with_players because there are only 7 wonders.
+    /// allocated wonders, all using side A (see [`Game::new_with_wonder_setup`] for other setups). `algorithms` must
+    /// have between 3 and 7 entries inclusive, corresponding to between 3 and 7 players.
+    ///
+    /// Every random choice in the game flows from `seed`: the game's own choices use a random number generator derived
+    /// from it, and each algorithm is reseeded with a further generator derived from it (see
+    /// [`PlayingAlgorithm::reseed`]). The same seed and the same algorithms therefore give an identical game.
+    pub fn new(algorithms: Vec<Box<dyn PlayingAlgorithm>>, seed: u64) -> Result<Game, GameError> {
+        Self::new_with_wonder_setup(algorithms, WonderSetup::Side(WonderSide::A), seed)
+    }
+
+    /// As [`Game::new`], but allocates wonders to players as described by the given [`WonderSetup`].
+    pub fn new_with_wonder_setup(
+        mut algorithms: Vec<Box<dyn PlayingAlgorithm>>,
+        wonder_setup: WonderSetup,
+        seed: u64,
+    ) -> Result<Game, GameError> {
+        // We have to check this here as well as in GameState::new because there are only 7 wonders.
         if algorithms.len() > 7 {
-            panic!("Must have at most seven players")
+            return Err(GameError::TooManyPlayers);
+        }
+
+        for (index, algorithm) in algorithms.iter_mut().enumerate() {
+            algorithm.reseed(Self::algorithm_rng(seed, index));
+        }
+        let wonders = Self::allocate_wonders(&mut algorithms, wonder_setup, &mut derive_rng(seed, SETUP_STREAM))?;
+        let players = wonders
+            .into_iter()
+            .map(|wonder| Player::new(wonder.wonder_type, wonder.wonder_side))
+            .collect();
+
+        Self::new_with_state(GameState::new(players, 0, vec![], vec![], seed)?, algorithms)
+    }
+
+    /// Creates a game playing on from the given state, with the given algorithm for each player. Intended to be used by
+    /// playing algorithms that need to simulate the rest of a game, by cloning the state of a position.
+    ///
+    /// Unlike [`Game::new`], the algorithms are not reseeded, so callers wanting a reproducible game should create them
+    /// with generators from [`Game::algorithm_rng`].
+    pub fn new_with_state(state: GameState, algorithms: Vec<Box<dyn PlayingAlgorithm>>) -> Result<Game, GameError> {
+        if algorithms.len() != state.players.len() {
+            return Err(GameError::WrongNumberOfAlgorithms {
+                players: state.players.len(),
+                algorithms: algorithms.len(),
+            });
+        }
+        Ok(Game {
+            state,
+            algorithms,
+            observers: vec![],
+            illegal_action_policy: IllegalActionPolicy::ForceDiscard,
+        })
+    }
+
+    /// Creates a game playing on from a restored state, with the given algorithm for each player. Intended for loading
+    /// a saved game (see [`crate::save`]). The algorithms are reseeded from the state's seed as in [`Game::new`], with
+    /// each generator moved on to the corresponding position in `rng_positions` (see
+    /// [`PlayingAlgorithm::rng_position`]) if there is one. The random choices of both the game and the algorithms then
+    /// continue as they would have done.
+    pub fn restore(
+        state: GameState,
+        mut algorithms: Vec<Box<dyn PlayingAlgorithm>>,
+        rng_positions: &[Option<u128>],
+    ) -> Result<Game, GameError> {
+        for (index, algorithm) in algorithms.iter_mut().enumerate() {
+            let mut rng = Self::algorithm_rng(state.seed, index);
+            if let Some(Some(position)) = rng_positions.get(index) {
+                rng.set_word_pos(*position);
+            }
+            algorithm.reseed(rng);
         }
+        Self::new_with_state(state, algorithms)
+    }
 
+    /// Returns the random number generator, derived from the given game seed, used by the algorithm for the player at
+    /// the given index.
+    pub fn algorithm_rng(seed: u64, player_index: usize) -> ChaCha8Rng {
+        derive_rng(seed, ALGORITHM_STREAM + player_index as u64)
+    }
+
+    /// Returns the wonder for each player (in the same order as `algorithms`) according to the given [`WonderSetup`].
+    /// The algorithms are only consulted when drafting wonders.
+    fn allocate_wonders(
+        algorithms: &mut [Box<dyn PlayingAlgorithm>],
+        wonder_setup: WonderSetup,
+        rng: &mut ChaCha8Rng,
+    ) -> Result<Vec<WonderBoard>, GameError> {
         let mut wonder_types: Vec<WonderType> = WonderType::iter().collect();
-        wonder_types.shuffle(&mut thread_rng());
+        wonder_types.shuffle(rng);
+        wonder_types.truncate(algorithms.len());
 
-        // For each player, pick a random wonder and deal seven random cards.
-        let sentient_players = algorithms
-            .into_iter()
-            .zip(wonder_types)
-            .map(|(algorithm, wonder_type)| SentientPlayer {
-                player: Player::new(wonder_type, WonderSide::A),
-                algorithm,
-            })
+        let random_side = |rng: &mut ChaCha8Rng| if rng.gen() { WonderSide::A } else { WonderSide::B };
+        let with_side = |wonder_type: WonderType, wonder_side: WonderSide| WonderBoard {
+            wonder_type,
+            wonder_side,
+        };
+
+        let wonders = match wonder_setup {
+            WonderSetup::RandomSide => {
+                let wonder_side = random_side(rng);
+                wonder_types
+                    .into_iter()
+                    .map(|wonder_type| with_side(wonder_type, wonder_side))
+                    .collect()
+            }
+            WonderSetup::Side(wonder_side) => wonder_types
+                .into_iter()
+                .map(|wonder_type| with_side(wonder_type, wonder_side))
+                .collect(),
+            WonderSetup::CoinFlip => wonder_types
+                .into_iter()
+                .map(|wonder_type| with_side(wonder_type, random_side(rng)))
+                .collect(),
+            WonderSetup::Explicit(wonders) => {
+                if wonders.len() != algorithms.len() {
+                    return Err(GameError::WrongNumberOfWonders {
+                        players: algorithms.len(),
+                        wonders: wonders.len(),
+                    });
+                }
+                for (index, wonder) in wonders.iter().enumerate() {
+                    if wonders[..index]
+                        .iter()
+                        .any(|other| other.wonder_type == wonder.wonder_type)
+                    {
+                        return Err(GameError::DuplicateWonder(wonder.wonder_type));
+                    }
+                }
+                wonders
+            }
+            WonderSetup::Draft => {
+                // Each player in turn chooses from the wonders not yet chosen, picking either side.
+                let mut remaining: Vec<WonderType> = WonderType::iter().collect();
+                let mut wonders = vec![];
+                for algorithm in algorithms.iter_mut() {
+                    let options: Vec<WonderBoard> = remaining
+                        .iter()
+                        .flat_map(|wonder_type| WonderSide::iter().map(move |side| with_side(*wonder_type, side)))
+                        .collect();
+                    let wonder = algorithm.choose_wonder(&options);
+                    if !options.contains(&wonder) {
+                        return Err(GameError::UnavailableWonder(wonder));
+                    }
+                    remaining.retain(|wonder_type| *wonder_type != wonder.wonder_type);
+                    wonders.push(wonder);
+                }
+                wonders
+            }
+        };
+        Ok(wonders)
+    }
+
+    /// Registers an observer to be told about each [`GameEvent`] as the game is played. Games have no observers by
+    /// default, so nothing is written while playing "virtual" games simulated by playing algorithms.
+    pub fn add_observer(&mut self, observer: Box<dyn GameObserver>) {
+        self.observers.push(observer);
+    }
+
+    /// Sets what to do when a playing algorithm returns an illegal action. Defaults to
+    /// [`IllegalActionPolicy::ForceDiscard`].
+    pub fn set_illegal_action_policy(&mut self, illegal_action_policy: IllegalActionPolicy) {
+        self.illegal_action_policy = illegal_action_policy;
+    }
+
+    pub fn illegal_action_policy(&self) -> IllegalActionPolicy {
+        self.illegal_action_policy
+    }
+
+    /// Plays the game! Returns the final scores of each player, in the same order as originally passed to [`new`], and
+    /// the resulting rankings, or an error if the game was aborted because of an illegal action (see
+    /// [`IllegalActionPolicy`]).
+    pub fn play(&mut self) -> Result<GameResult, GameError> {
+        loop {
+            if let Some(result) = self.play_turn()? {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Plays the rest of the current turn, stopping at the start of the next one so the game can be saved in between
+    /// (see [`crate::save`]). The final turn is played through to the end of the game, returning the result as
+    /// [`Game::play`] does. Otherwise returns `None`.
+    pub fn play_turn(&mut self) -> Result<Option<GameResult>, GameError> {
+        let turn = self.state.turn;
+        // Once the final turn is over, carry on through final scoring rather than stopping part way through it.
+        while self.state.step != Step::GameOver && (self.state.turn == turn || self.state.age().is_err()) {
+            self.do_step()?;
+        }
+        if self.state.step != Step::GameOver {
+            return Ok(None);
+        }
+        let result = self.state.result();
+        self.notify(GameEvent::GameEnded(result.clone()));
+        Ok(Some(result))
+    }
+
+    /// Executes a turn of the game, taking each step of the turn in turn (see [`Step`]).
+    #[cfg(test)]
+    fn do_turn(&mut self) -> Result<(), GameError> {
+        // Returns an error if the game is over.
+        self.age()?;
+        let turn = self.state.turn;
+        while self.state.turn == turn {
+            self.do_step()?;
+        }
+        Ok(())
+    }
+
+    /// Gets the decisions needed for the next step of the game from the players' algorithms, and moves the game state
+    /// on accordingly.
+    fn do_step(&mut self) -> Result<(), GameError> {
+        let events = match self.state.step.clone() {
+            Step::Deal => self.state.deal()?,
+            Step::Actions { player_indexes, .. } => {
+                let actions = self.take_actions(&player_indexes)?;
+                self.state.apply(actions)?
+            }
+            Step::DiscardBuild { player_index, cards } => {
+                let public_players = self.state.public_players();
+                let visible_game = VisibleGame {
+                    public_players: &public_players,
+                    history: &self.state.history,
+                    player_index,
+                    turn: self.state.turn,
+                };
+                let card = self.algorithms[player_index].get_discard_build(
+                    &self.state.players[player_index],
+                    &visible_game,
+                    &cards,
+                );
+                self.state.build_from_discard(card)?
+            }
+            Step::CopyGuild { player_index, guilds } => {
+                let public_players = self.state.public_players();
+                // The game is over, so present the state as at the end of the final turn.
+                let visible_game = VisibleGame {
+                    public_players: &public_players,
+                    history: &self.state.history,
+                    player_index,
+                    turn: 17,
+                };
+                let guild = self.algorithms[player_index].get_guild_to_copy(
+                    &self.state.players[player_index],
+                    &visible_game,
+                    &guilds,
+                );
+                self.state.copy_guild(guild)?
+            }
+            Step::GameOver => vec![],
+        };
+        for event in events {
+            self.notify(event);
+        }
+        Ok(())
+    }
+
+    /// Calls [`Game::take_action`] for each of the players with the given indexes, returning the actions they'll
+    /// perform.
+    fn take_actions(&mut self, player_indexes: &[usize]) -> Result<Vec<Action>, GameError> {
+        // public_players is an immutable snapshot of the game state before players start moving, so that each moves
+        // "simultaneously". The history is moved out of the state while players choose their actions, so players can
+        // see it while observers are told about any illegal actions.
+        let public_players = self.state.public_players();
+        let history = mem::take(&mut self.state.history);
+        let actions = player_indexes
+            .iter()
+            .map(|index| self.take_action(*index, &public_players, &history))
             .collect();
+        self.state.history = history;
+        actions
+    }
+
+    /// Asks the player with the given index for their next action, returning the action they'll perform.
+    /// `public_players` and `history` are the snapshot of the game state the player sees when choosing their action.
+    ///
+    /// If the algorithm returns an illegal action, it is handled according to the game's [`IllegalActionPolicy`].
+    fn take_action(
+        &mut self,
+        index: usize,
+        public_players: &[PublicPlayer],
+        history: &[RevealedAction],
+    ) -> Result<Action, GameError> {
+        let visible_game = VisibleGame {
+            public_players,
+            history,
+            player_index: index,
+            turn: self.state.turn,
+        };
+        let mut attempts = 0;
+        loop {
+            let player = &self.state.players[index];
+            let action = self.algorithms[index].get_next_action(player, &visible_game);
+            let reason = match player.check_action(&action, &visible_game) {
+                Ok(()) => return Ok(action),
+                Err(reason) => reason,
+            };
+            let illegal_action = IllegalAction {
+                player_index: index,
+                turn: self.state.turn,
+                action,
+                reason,
+            };
+            self.state.illegal_actions.push(illegal_action.clone());
+
+            attempts += 1;
+            self.notify(GameEvent::IllegalActionAttempted {
+                attempt: attempts,
+                illegal_action: illegal_action.clone(),
+            });
 
-        Self::new_with_players(sentient_players, 0, OutputMode::WriteOutput)
+            match self.illegal_action_policy {
+                IllegalActionPolicy::Retry(max_attempts) if attempts < max_attempts => {
+                    self.algorithms[index].illegal_action(&illegal_action.action, reason);
+                }
+                IllegalActionPolicy::ForceDiscard => {
+                    // Discard the card the player chose, or if they don't have it, any card in their hand.
+                    let hand = self.state.players[index].hand();
+                    let card = match hand.iter().find(|card| *card == illegal_action.action.card()) {
+                        Some(card) => *card,
+                        None => *hand
+                            .first()
+                            .ok_or_else(|| GameError::IllegalAction(illegal_action.clone()))?,
+                    };
+                    return Ok(Action::Discard(card));
+                }
+                _ => return Err(GameError::IllegalAction(illegal_action)),
+            }
+        }
     }
 
-    /// Creates a new game with the given [`SentientPlayer`]s and starting from the given turn. Intended to be used by
-    /// playing algorithms that need to simulate a game starting from the position of the current game.
-    pub fn new_with_players(sentient_players: Vec<SentientPlayer>, turn: u32, output_mode: OutputMode) -> Game {
-        if sentient_players.len() < 3 {
-            panic!("Must have at least three players")
+    /// Tells every observer about the given event.
+    fn notify(&mut self, event: GameEvent) {
+        for observer in self.observers.iter_mut() {
+            observer.on_event(&event);
         }
-        if sentient_players.len() > 7 {
-            panic!("Must have at most seven players")
+    }
+
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    pub fn algorithms(&self) -> &Vec<Box<dyn PlayingAlgorithm>> {
+        &self.algorithms
+    }
+
+    pub fn player_count(&self) -> u32 {
+        self.state.player_count()
+    }
+
+    /// Returns the current age being played, or an error if the game is over.
+    pub fn age(&self) -> Result<Age, GameError> {
+        self.state.age()
+    }
+}
+
+/// The whole state of a game, without the algorithms playing it. The game moves on one [`Step`] at a time as the
+/// players' decisions are applied, so playing algorithms can cheaply clone the state of a position and simulate
+/// different continuations from it.
+#[derive(Debug, Clone)]
+pub struct GameState {
+    /// The players in the game. Moving through the vector starting from index 0 is equivalent to moving clockwise
+    /// around the table of players. The player at the end of the vector also sits next to player at index 0, of course.
+    /// For example, for a game of 5 players, the player at index 1 sits to the left (ie. clockwise) of the player at
+    /// index 0, and the player at index 4 sits to the right (ie. anti-clockwise) of the player at index 0.
+    players: Vec<Player>,
+
+    /// The game turn. Runs from 0 to 17 for 3 ages of 6 turns each, and is 18 once the final turn is over.
+    turn: u32,
+
+    /// The discard pile. Starts empty and gains the final, unplayed card from each player at the end of each age.
+    discard_pile: Vec<Card>,
+
+    /// The actions revealed by every player so far, in the order they were performed. States created part way through
+    /// a game only have the history they were created with.
+    history: Vec<RevealedAction>,
+
+    /// Every illegal action returned by a playing algorithm so far, in the order they were returned (see
+    /// [`IllegalActionPolicy`]).
+    illegal_actions: Vec<IllegalAction>,
+
+    /// The seed all random choices in the game flow from (see [`Game::new`]).
+    seed: u64,
+
+    /// What the game is waiting for before it can move on.
+    step: Step,
+
+    /// The number of wonder stages each player had built at the start of the turn, used to find the players who built
+    /// a stage allowing them to build from the discard pile this turn.
+    wonder_stages_before: Vec<usize>,
+}
+
+/// The next step of a game: what a [`GameState`] is waiting for before it can move on.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Step {
+    /// New hands are to be dealt at the start of an age (see [`GameState::deal`]).
+    Deal,
+    /// An action from each of the given players, in the same order (see [`GameState::apply`]). `seventh_card` is
+    /// `true` if these are extra actions taken with the final card of an age (ie. on the Hanging Gardens of Babylon).
+    Actions {
+        player_indexes: Vec<usize>,
+        seventh_card: bool,
+    },
+    /// The player's choice of card, if any, to build for free from the given cards in the discard pile (see
+    /// [`GameState::build_from_discard`]).
+    DiscardBuild { player_index: usize, cards: Vec<Card> },
+    /// The player's choice of guild, if any, to copy from the given guilds of their neighbours (see
+    /// [`GameState::copy_guild`]).
+    CopyGuild { player_index: usize, guilds: Vec<Card> },
+    /// Nothing: the game is over (see [`GameState::result`]).
+    GameOver,
+}
+
+#[allow(dead_code)]
+impl GameState {
+    /// Creates the state of a game at the start of the given turn, with the given players, discard pile and history.
+    /// If it's the start of an age and nobody holds any cards, new hands are dealt first (see [`Step::Deal`]).
+    /// Otherwise the players already hold their hands for the turn, and the game waits for their actions.
+    ///
+    /// The game's own random choices flow from `seed` (see [`Game::new`]).
+    pub fn new(
+        players: Vec<Player>,
+        turn: u32,
+        discard_pile: Vec<Card>,
+        history: Vec<RevealedAction>,
+        seed: u64,
+    ) -> Result<GameState, GameError> {
+        if players.len() < 3 {
+            return Err(GameError::TooFewPlayers);
+        }
+        if players.len() > 7 {
+            return Err(GameError::TooManyPlayers);
         }
         if turn > 17 {
-            panic!("Turn cannot be larger than 17")
+            return Err(GameError::InvalidTurn(turn));
         }
-        Game {
-            sentient_players,
+        let mut state = GameState {
+            players,
             turn,
-            discard_pile: vec![],
-            output_mode,
+            discard_pile,
+            history,
+            illegal_actions: vec![],
+            seed,
+            step: Step::Deal,
+            wonder_stages_before: vec![],
+        };
+        state.wonder_stages_before = state.wonder_stages();
+        if !turn.is_multiple_of(6) || state.players.iter().any(|player| !player.hand().is_empty()) {
+            state.step = state.actions_step();
         }
+        Ok(state)
     }
 
-    /// Plays the game! Returns the final scores of each player in the same order as originally passed to [`new`].
-    pub fn play(&mut self) -> Vec<i32> {
-        for _ in self.turn..18 {
-            self.do_turn();
+    /// Deals new hands at the start of an age, adding any cards still held to the discard pile. Returns the events
+    /// that happened.
+    pub fn deal(&mut self) -> Result<Vec<GameEvent>, GameError> {
+        if self.step != Step::Deal {
+            return Err(GameError::UnexpectedStep);
         }
-        self.sentient_players
-            .iter()
-            .map(|sentient_player| sentient_player.player.strength() as i32)
-            .collect()
+        let age = self.age()?;
+        let mut deck = card::new_deck(
+            &age,
+            self.player_count(),
+            &mut derive_rng(self.seed, DEAL_STREAM + (self.turn / 6) as u64),
+        );
+        let mut events = vec![GameEvent::AgeStarted(age)];
+        for (player_index, player) in self.players.iter_mut().enumerate() {
+            let old_hand = player.swap_hand(deck.drain(0..7).collect());
+            player.reset_free_build();
+            self.discard_pile.extend(old_hand);
+            events.push(GameEvent::CardsDealt {
+                player_index,
+                hand: player.hand().clone(),
+            });
+        }
+        self.step = self.actions_step();
+        Ok(events)
     }
 
-    /// Executes a turn of the game. Gets an [`Action`] from each [`Player`] and updates the game state accordingly.
-    fn do_turn(&mut self) {
-        // At the start of each age, deal new cards and add any remaining cards to the discard pile.
-        if self.turn % 6 == 0 {
-            let mut deck = card::new_deck(&self.age(), self.player_count());
-            for sentient_player in self.sentient_players.iter_mut() {
-                let old_hand = sentient_player.player.swap_hand(deck.drain(0..7).collect());
-                for card in old_hand {
-                    self.discard_pile.push(card);
-                }
+    /// Performs the given actions for the players waiting to act (see [`Step::Actions`]), one action per player in the
+    /// same order, and moves the game on to its next step. Returns the events that happened, or an error if any of the
+    /// actions are illegal, in which case the state is unchanged.
+    ///
+    /// The players act simultaneously: each action is checked against the state at the start of the step, and coins
+    /// paid to neighbours for borrowing are only received once everyone has acted.
+    pub fn apply(&mut self, actions: Vec<Action>) -> Result<Vec<GameEvent>, GameError> {
+        let (player_indexes, seventh_card) = match &self.step {
+            Step::Actions {
+                player_indexes,
+                seventh_card,
+            } => (player_indexes.clone(), *seventh_card),
+            _ => return Err(GameError::UnexpectedStep),
+        };
+        if actions.len() != player_indexes.len() {
+            return Err(GameError::WrongNumberOfActions {
+                expected: player_indexes.len(),
+                actual: actions.len(),
+            });
+        }
+
+        let public_players = self.public_players();
+        for (player_index, action) in player_indexes.iter().zip(&actions) {
+            let visible_game = VisibleGame {
+                public_players: &public_players,
+                history: &self.history,
+                player_index: *player_index,
+                turn: self.turn,
+            };
+            if let Err(reason) = self.players[*player_index].check_action(action, &visible_game) {
+                return Err(GameError::IllegalAction(IllegalAction {
+                    player_index: *player_index,
+                    turn: self.turn,
+                    action: action.clone(),
+                    reason,
+                }));
             }
         }
 
-        // Do actions. public_players is an immutable snapshot of the game state before players start moving, so
-        // that each moves "simultaneously".
-        let public_players: Vec<PublicPlayer> = self
-            .sentient_players
-            .iter()
-            .map(|sentient_player| PublicPlayer::new(&sentient_player.player))
-            .collect();
-        let actions: Vec<Action> = (0..self.sentient_players.len())
-            .map(|index| {
-                let (right_player, sentient_player, left_player) =
-                    Self::get_mutable_player_and_neighbours(&mut self.sentient_players, index);
+        if !seventh_card {
+            self.wonder_stages_before = self.wonder_stages();
+        }
+        let mut coin_transfers = vec![];
+        for (player_index, action) in player_indexes.iter().zip(&actions) {
+            let visible_game = VisibleGame {
+                public_players: &public_players,
+                history: &self.history,
+                player_index: *player_index,
+                turn: self.turn,
+            };
+            self.players[*player_index].do_action(
+                action,
+                &visible_game,
+                &mut self.discard_pile,
+                &mut coin_transfers,
+            )?;
+        }
+        let mut events = vec![];
+        for (player_index, action) in player_indexes.into_iter().zip(actions) {
+            self.reveal(player_index, action, &coin_transfers, seventh_card, &mut events);
+        }
+
+        // Only now that everyone has acted do players receive coins paid to them by their neighbours. This means
+        // coins received can't be spent in the same turn, and the results don't depend on the order of the players.
+        self.settle_coin_transfers(coin_transfers, &mut events);
+
+        // At the end of each age, players able to play the seventh card of an age take an extra action with the final
+        // card in their hand.
+        if !seventh_card && self.turn % 6 == 5 {
+            if let Some(step) = self.seventh_card_step() {
+                self.step = step;
+                return Ok(events);
+            }
+        }
+
+        // At the end of each age, everyone else's final card is discarded.
+        if self.turn % 6 == 5 {
+            for player in self.players.iter_mut() {
+                let old_hand = player.swap_hand(vec![]);
+                self.discard_pile.extend(old_hand);
+            }
+        }
+        self.next_discard_build(0, &mut events)?;
+        Ok(events)
+    }
+
+    /// Moves straight on to the seventh card of the age, as if everyone had already taken their action on the final
+    /// turn of the age (see [`GameState::apply`]). Intended for playing algorithms asked to play the seventh card, who
+    /// need to simulate the rest of the game from that point. Returns an error unless the game is waiting for
+    /// everyone's action on the final turn of an age, and a player holding a card is able to play the seventh card.
+    pub fn skip_to_seventh_card(&mut self) -> Result<(), GameError> {
+        if self.step != self.actions_step() || self.turn % 6 != 5 {
+            return Err(GameError::UnexpectedStep);
+        }
+        self.step = self.seventh_card_step().ok_or(GameError::UnexpectedStep)?;
+        Ok(())
+    }
+
+    /// Builds the given card from the discard pile for free for the player allowed to (see [`Step::DiscardBuild`]),
+    /// or builds nothing if `None`, and moves the game on to its next step. A card that isn't in the discard pile or
+    /// can't be built is ignored. Returns the events that happened.
+    pub fn build_from_discard(&mut self, card: Option<Card>) -> Result<Vec<GameEvent>, GameError> {
+        let player_index = match &self.step {
+            Step::DiscardBuild { player_index, .. } => *player_index,
+            _ => return Err(GameError::UnexpectedStep),
+        };
+        let mut events = vec![];
+        if let Some(card) = card {
+            if let Some(position) = self.discard_pile.iter().position(|c| *c == card) {
+                let public_players = self.public_players();
                 let visible_game = VisibleGame {
                     public_players: &public_players,
-                    player_index: index,
+                    history: &self.history,
+                    player_index,
                     turn: self.turn,
                 };
-                let action = sentient_player
-                    .algorithm
-                    .get_next_action(&sentient_player.player, &visible_game);
-                sentient_player.player.do_action(
-                    &action,
-                    &visible_game,
-                    &mut left_player.player,
-                    &mut right_player.player,
-                    &mut self.discard_pile,
-                );
-                action
+                if self.players[player_index].build_from_discard(&card, &visible_game) {
+                    self.discard_pile.swap_remove(position);
+                    self.history.push(RevealedAction {
+                        turn: self.turn,
+                        player_index,
+                        action: PublicAction::BuildFromDiscard(card),
+                        payments: vec![],
+                        seventh_card: false,
+                    });
+                    events.push(GameEvent::BuiltFromDiscard { player_index, card });
+                }
+            }
+        }
+        self.next_discard_build(player_index + 1, &mut events)?;
+        Ok(events)
+    }
+
+    /// Copies the given guild for final scoring for the player allowed to (see [`Step::CopyGuild`]), or copies nothing
+    /// if `None`, and moves the game on to its next step. A guild that can't be copied is ignored. Returns the events
+    /// that happened.
+    pub fn copy_guild(&mut self, guild: Option<Card>) -> Result<Vec<GameEvent>, GameError> {
+        let player_index = match &self.step {
+            Step::CopyGuild { player_index, .. } => *player_index,
+            _ => return Err(GameError::UnexpectedStep),
+        };
+        let mut events = vec![];
+        if let Some(guild) = guild {
+            let public_players = self.public_players();
+            let num_players = public_players.len();
+            let left = &public_players[(player_index + 1) % num_players];
+            let right = &public_players[(player_index + num_players - 1) % num_players];
+            if self.players[player_index].copy_guild(&guild, left, right) {
+                events.push(GameEvent::GuildCopied { player_index, guild });
+            }
+        }
+        self.next_guild_copy(player_index + 1);
+        Ok(events)
+    }
+
+    /// Returns the scores of each player and the resulting rankings, as if the game ended now.
+    pub fn result(&self) -> GameResult {
+        let public_players = self.public_players();
+        let num_players = public_players.len();
+        let scores = self
+            .players
+            .iter()
+            .enumerate()
+            .map(|(index, player)| {
+                let left = &public_players[(index + 1) % num_players];
+                let right = &public_players[(index + num_players - 1) % num_players];
+                player.score(left, right)
             })
             .collect();
+        let coins = public_players.iter().map(|public_player| public_player.coins).collect();
+        GameResult::new(scores, coins)
+    }
 
-        if self.output_mode == OutputMode::WriteOutput {
-            actions
-                .iter()
-                .enumerate()
-                .for_each(|(i, action)| println!("Player {}: {}", i + 1, action));
+    /// Returns the step waiting for every player's action this turn.
+    fn actions_step(&self) -> Step {
+        Step::Actions {
+            player_indexes: (0..self.players.len()).collect(),
+            seventh_card: false,
         }
+    }
 
-        // Pass cards.
-        let num_players = self.sentient_players.len();
-        let mut hand = vec![];
-        for i in 0..num_players + 1 {
-            let index = if Self::age(&self) == Age::Second {
-                // In the second age, we pass cards anti-clockwise.
-                num_players - i
-            } else {
-                // Otherwise, pass clockwise.
-                i
-            } % num_players;
-            hand = self.sentient_players[index].player.swap_hand(hand);
+    /// Returns the step waiting for the seventh card of the age from every player able to play it, if there are any.
+    fn seventh_card_step(&self) -> Option<Step> {
+        let player_indexes: Vec<usize> = (0..self.players.len())
+            .filter(|index| {
+                let player = &self.players[*index];
+                player.can_play_seventh_card() && !player.hand().is_empty()
+            })
+            .collect();
+        if player_indexes.is_empty() {
+            None
+        } else {
+            Some(Step::Actions {
+                player_indexes,
+                seventh_card: true,
+            })
         }
+    }
 
-        self.turn += 1;
+    /// Returns the number of wonder stages each player has built.
+    fn wonder_stages(&self) -> Vec<usize> {
+        self.players
+            .iter()
+            .map(|player| player.built_wonder_stages().len())
+            .collect()
     }
 
-    pub fn player_count(&self) -> u32 {
-        self.sentient_players.len() as u32
+    /// Adds the given action, performed by the player with the given index, to the public history and adds an event
+    /// for it to `events`. The coins the player paid to their neighbours are taken from `coin_transfers`.
+    fn reveal(
+        &mut self,
+        player_index: usize,
+        action: Action,
+        coin_transfers: &[CoinTransfer],
+        seventh_card: bool,
+        events: &mut Vec<GameEvent>,
+    ) {
+        self.history.push(RevealedAction {
+            turn: self.turn,
+            player_index,
+            action: PublicAction::from(&action),
+            payments: coin_transfers
+                .iter()
+                .filter(|coin_transfer| coin_transfer.from == player_index)
+                .cloned()
+                .collect(),
+            seventh_card,
+        });
+        events.push(GameEvent::ActionRevealed {
+            player_index,
+            action,
+            seventh_card,
+        });
     }
 
-    /// Returns the current age being played.
-    pub fn age(&self) -> Age {
-        Self::age_internal(self.turn)
+    /// Credits each player with the coins transferred to them. The paying players have already had their coins
+    /// deducted.
+    fn settle_coin_transfers(&mut self, coin_transfers: Vec<CoinTransfer>, events: &mut Vec<GameEvent>) {
+        for coin_transfer in coin_transfers {
+            self.players[coin_transfer.to].add_coins(coin_transfer.coins);
+            events.push(GameEvent::CoinsTransferred(coin_transfer));
+        }
     }
 
-    /// Shared between Game::age and VisibleGame::age. Returns the age given a turn number.
-    fn age_internal(turn: u32) -> Age {
-        match turn {
-            0..=5 => Age::First,
-            6..=11 => Age::Second,
-            12..=17 => Age::Third,
-            _ => panic!("Unknown turn!"),
+    /// Moves on to the first player, from the given index onwards, who built a wonder stage with
+    /// [`Power::BuildFromDiscard`] this turn and has something to build from the discard pile. Players do so at the end
+    /// of the turn, once every card discarded this turn (including the final cards of an age) is in the discard pile.
+    /// If there are no such players, the turn ends.
+    fn next_discard_build(&mut self, from: usize, events: &mut Vec<GameEvent>) -> Result<(), GameError> {
+        for player_index in from..self.players.len() {
+            let player = &self.players[player_index];
+            let allowed = player
+                .wonder()
+                .stages()
+                .iter()
+                .take(player.built_wonder_stages().len())
+                .skip(self.wonder_stages_before[player_index])
+                .flat_map(|stage| &stage.powers)
+                .any(|power| matches!(power, Power::BuildFromDiscard));
+            if !allowed {
+                continue;
+            }
+            let cards: Vec<Card> = self
+                .discard_pile
+                .iter()
+                .filter(|card| !player.has_built(card))
+                .copied()
+                .collect();
+            if !cards.is_empty() {
+                self.step = Step::DiscardBuild { player_index, cards };
+                return Ok(());
+            }
         }
+        self.end_turn(events)
     }
 
-    /// Given the index of a player, returns a mutable borrow of that player, as well as the left and right neighbours
-    /// of the player. This is super-horrible in Rust as far as I can tell. Perhaps there's a better way...
-    fn get_mutable_player_and_neighbours(
-        players: &mut Vec<SentientPlayer>,
-        index: usize,
-    ) -> (&mut SentientPlayer, &mut SentientPlayer, &mut SentientPlayer) {
-        if index == 0 {
-            // player=0, left=1, right=n
-            let (player, after) = players.split_first_mut().unwrap();
-            let (right_player, rest) = after.split_last_mut().unwrap();
-            let (left_player, _) = rest.split_first_mut().unwrap();
-            (right_player, player, left_player)
-        } else if index == players.len() - 1 {
-            // player=n, left=0, right=n-1
-            let (player, before) = players.split_last_mut().unwrap();
-            let (left_player, rest) = before.split_first_mut().unwrap();
-            let (right_player, _) = rest.split_last_mut().unwrap();
-            (right_player, player, left_player)
+    /// Ends the current turn, resolving military conflicts at the end of an age and otherwise passing the hands on, and
+    /// moves on to the next turn.
+    fn end_turn(&mut self, events: &mut Vec<GameEvent>) -> Result<(), GameError> {
+        let age = self.age()?;
+        if self.turn % 6 == 5 {
+            // At the end of each age, each player fights a military conflict with each of their neighbours.
+            self.resolve_conflicts(&age, events);
         } else {
-            // player=i, left=i+1, right=i-1
-            let (before, player_and_after) = players.split_at_mut(index);
-            let (player_slice, after) = player_and_after.split_at_mut(1);
-            (&mut before[index - 1], &mut player_slice[0], &mut after[0])
+            // Pass cards.
+            let num_players = self.players.len();
+            let mut hand = vec![];
+            for i in 0..num_players + 1 {
+                let index = if age == Age::Second {
+                    // In the second age, we pass cards anti-clockwise.
+                    num_players - i
+                } else {
+                    // Otherwise, pass clockwise.
+                    i
+                } % num_players;
+                hand = self.players[index].swap_hand(hand);
+            }
         }
+
+        self.turn += 1;
+        if self.turn == 18 {
+            self.next_guild_copy(0);
+        } else if self.turn.is_multiple_of(6) {
+            self.step = Step::Deal;
+        } else {
+            self.step = self.actions_step();
+        }
+        Ok(())
     }
-}
 
-/// A [`Player`] and a [`PlayingAlgorithm`]. `PlayingAlgorithm` can't live inside `Player` because we need to allow
-/// algorithm implementations to maintain state and therefore be mutable when called. They also need access to the data
-/// and methods of `Player` in order to make decisions. Therefore, we'd have a mutable and immutable borrow at the same
-/// time.
-#[derive(Debug)]
-pub struct SentientPlayer {
-    pub player: Player,
-    pub algorithm: Box<dyn PlayingAlgorithm>,
+    /// Resolves the military conflicts at the end of the current age. Each player compares their shields with both of
+    /// their neighbours, gaining a victory or defeat token for each.
+    fn resolve_conflicts(&mut self, age: &Age, events: &mut Vec<GameEvent>) {
+        let shields: Vec<u32> = self.players.iter().map(|player| player.shields()).collect();
+        let num_players = shields.len();
+        for (index, player) in self.players.iter_mut().enumerate() {
+            player.resolve_conflict(shields[(index + 1) % num_players], age);
+            player.resolve_conflict(shields[(index + num_players - 1) % num_players], age);
+        }
+
+        let military_tokens = self
+            .players
+            .iter()
+            .map(|player| player.military_tokens().clone())
+            .collect();
+        events.push(GameEvent::ConflictResolved {
+            age: age.clone(),
+            shields,
+            military_tokens,
+        });
+    }
+
+    /// Moves on to the first player, from the given index onwards, who can copy one of their neighbours' guilds for
+    /// final scoring (see [`Power::CopyGuild`]). If there are no such players, the game is over.
+    fn next_guild_copy(&mut self, from: usize) {
+        let public_players = self.public_players();
+        let num_players = public_players.len();
+        for player_index in from..num_players {
+            let left = &public_players[(player_index + 1) % num_players];
+            let right = &public_players[(player_index + num_players - 1) % num_players];
+            let guilds = self.players[player_index].guilds_to_copy(left, right);
+            if !guilds.is_empty() {
+                self.step = Step::CopyGuild { player_index, guilds };
+                return;
+            }
+        }
+        self.step = Step::GameOver;
+    }
+
+    /// Returns a snapshot of the public state of every player.
+    pub fn public_players(&self) -> Vec<PublicPlayer> {
+        self.players.iter().map(PublicPlayer::new).collect()
+    }
+
+    pub fn players(&self) -> &Vec<Player> {
+        &self.players
+    }
+
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    pub fn discard_pile(&self) -> &Vec<Card> {
+        &self.discard_pile
+    }
+
+    pub fn history(&self) -> &Vec<RevealedAction> {
+        &self.history
+    }
+
+    /// Returns every illegal action returned by a playing algorithm so far, in the order they were returned.
+    pub fn illegal_actions(&self) -> &Vec<IllegalAction> {
+        &self.illegal_actions
+    }
+
+    /// Replaces the illegal actions recorded so far. Intended for restoring a saved game (see [`crate::save`]).
+    pub fn set_illegal_actions(&mut self, illegal_actions: Vec<IllegalAction>) {
+        self.illegal_actions = illegal_actions;
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn step(&self) -> &Step {
+        &self.step
+    }
+
+    pub fn player_count(&self) -> u32 {
+        self.players.len() as u32
+    }
+
+    /// Returns the current age being played, or an error if the game is over.
+    pub fn age(&self) -> Result<Age, GameError> {
+        Self::age_internal(self.turn)
+    }
+
+    /// Shared between GameState::age and VisibleGame::age. Returns the age given a turn number.
+    fn age_internal(turn: u32) -> Result<Age, GameError> {
+        match turn {
+            0..=5 => Ok(Age::First),
+            6..=11 => Ok(Age::Second),
+            12..=17 => Ok(Age::Third),
+            _ => Err(GameError::InvalidTurn(turn)),
+        }
+    }
 }
 
 /// The state of the game visible to all players (ie. excluding things like players' hands).
@@ -218,6 +942,8 @@ pub struct SentientPlayer {
 pub struct VisibleGame<'a> {
     /// All players in the game.
     pub public_players: &'a [PublicPlayer],
+    /// The actions revealed by every player so far, in the order they were performed.
+    pub history: &'a [RevealedAction],
     /// The index of the player this has been generated for.
     pub player_index: usize,
     pub turn: u32,
@@ -245,163 +971,1036 @@ impl<'a> VisibleGame<'a> {
     }
 
     /// Returns the current age being played.
-    pub fn age(&self) -> Age {
-        Game::age_internal(self.turn)
+    pub fn age(&self) -> Result<Age, GameError> {
+        GameState::age_internal(self.turn)
+    }
+
+    /// Returns the actions revealed in the given turn, in the order they were performed.
+    pub fn history_for_turn(&self, turn: u32) -> impl Iterator<Item = &RevealedAction> {
+        self.history
+            .iter()
+            .filter(move |revealed_action| revealed_action.turn == turn)
+    }
+}
+
+/// The result of a finished game: the score of each player and the final rankings.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GameResult {
+    /// The final score of each player, in the same order as the players in the game.
+    pub scores: Vec<ScoreBreakdown>,
+    /// The number of coins each player finished with, used to break ties.
+    pub coins: Vec<i32>,
+    /// Player indexes grouped by finishing position. The first entry contains the winner, the second the runner up, and
+    /// so on. Players sharing an entry have the same score and the same number of coins, and therefore share that
+    /// position.
+    pub rankings: Vec<Vec<usize>>,
+}
+
+impl GameResult {
+    /// Creates a [`GameResult`] from each player's score and coins, ranking players by total score. As per the official
+    /// rules, ties are broken by the number of coins, and players with the same score and coins share their position.
+    pub fn new(scores: Vec<ScoreBreakdown>, coins: Vec<i32>) -> GameResult {
+        let rankings = (0..scores.len())
+            .map(|index| ((scores[index].total(), coins[index]), index))
+            .sorted_by_key(|(key, _)| *key)
+            .rev()
+            .group_by(|(key, _)| *key)
+            .into_iter()
+            .map(|(_, group)| group.map(|(_, index)| index).sorted().collect())
+            .collect();
+        GameResult {
+            scores,
+            coins,
+            rankings,
+        }
+    }
+
+    /// Returns the indexes of the winning players. There is more than one winner if players share the victory.
+    pub fn winners(&self) -> &Vec<usize> {
+        &self.rankings[0]
+    }
+
+    /// Returns `true` if the player with the given index won the game, including sharing the victory.
+    pub fn is_winner(&self, player_index: usize) -> bool {
+        self.winners().contains(&player_index)
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
-pub enum OutputMode {
-    WriteOutput,
-    NoOutput,
+/// How wonders are allocated to players when creating a new game with [`Game::new_with_wonder_setup`].
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub enum WonderSetup {
+    /// Each player gets a random wonder. A single random side is used by all players.
+    RandomSide,
+    /// Each player gets a random wonder, all using the given side.
+    Side(WonderSide),
+    /// Each player gets a random wonder, and the side of each is chosen at random (a coin flip per player).
+    CoinFlip,
+    /// Each player gets the given wonder. There must be one entry per player, in seat order, each with a different
+    /// wonder.
+    Explicit(Vec<WonderBoard>),
+    /// Each player in turn chooses their wonder and side from those not already chosen, using
+    /// [`PlayingAlgorithm::choose_wonder`].
+    Draft,
+}
+
+/// What to do when a playing algorithm returns an illegal action. Whatever the policy, each illegal action is recorded
+/// in the game state (see [`GameState::illegal_actions`]) and observers are notified of it.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[allow(dead_code)]
+pub enum IllegalActionPolicy {
+    /// Asks the algorithm for another action (after notifying it using [`PlayingAlgorithm::illegal_action`]), up to
+    /// the given total number of attempts. If the algorithm still hasn't returned a legal action, the game is aborted.
+    Retry(u32),
+    /// The card the algorithm chose is discarded instead (or, if the player doesn't have that card, any card from their
+    /// hand).
+    ForceDiscard,
+    /// The game is aborted.
+    Abort,
+}
+
+/// An illegal action returned by a playing algorithm.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct IllegalAction {
+    /// The index of the player whose algorithm returned the action.
+    pub player_index: usize,
+    pub turn: u32,
+    pub action: Action,
+    pub reason: IllegalActionReason,
+}
+
+impl Display for IllegalAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Player {} made an illegal action on turn {} ({}): {:?}",
+            self.player_index + 1,
+            self.turn + 1,
+            self.reason,
+            self.action
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::action::Action;
+    use crate::action::{Action, Borrow, Borrowing};
     use crate::algorithms::random::Random;
+    use crate::resources::Resource;
+    use std::cell::RefCell;
+    use std::rc::Rc;
 
     #[test]
-    #[should_panic(expected = "Must have at least three players")]
-    fn new_panics_if_less_than_three_players() {
-        Game::new(vec![Box::new(Random {}), Box::new(Random {})]);
+    fn new_returns_error_if_less_than_three_players() {
+        assert!(matches!(
+            Game::new(vec![Box::new(Random::default()), Box::new(Random::default())], 0),
+            Err(GameError::TooFewPlayers)
+        ));
     }
 
     #[test]
-    #[should_panic(expected = "Must have at most seven players")]
-    fn new_panics_if_more_than_seven_players() {
-        Game::new(vec![
-            Box::new(Random {}),
-            Box::new(Random {}),
-            Box::new(Random {}),
-            Box::new(Random {}),
-            Box::new(Random {}),
-            Box::new(Random {}),
-            Box::new(Random {}),
-            Box::new(Random {}),
-        ]);
+    fn new_returns_error_if_more_than_seven_players() {
+        let result = Game::new(
+            vec![
+                Box::new(Random::default()),
+                Box::new(Random::default()),
+                Box::new(Random::default()),
+                Box::new(Random::default()),
+                Box::new(Random::default()),
+                Box::new(Random::default()),
+                Box::new(Random::default()),
+                Box::new(Random::default()),
+            ],
+            0,
+        );
+        assert!(matches!(result, Err(GameError::TooManyPlayers)));
+    }
+
+    #[test]
+    fn new_game_gives_everyone_side_a() {
+        for seed in 0..10 {
+            let game = Game::new(discarding_algorithms(7), seed).unwrap();
+            assert!(game.wonders().iter().all(|wonder| wonder.wonder_side == WonderSide::A));
+        }
+    }
+
+    #[test]
+    fn new_with_wonder_setup_gives_everyone_the_same_random_side() {
+        let game = Game::new_with_wonder_setup(discarding_algorithms(5), WonderSetup::RandomSide, 0).unwrap();
+        let sides: Vec<WonderSide> = game.wonders().iter().map(|wonder| wonder.wonder_side).collect();
+        assert!(sides.iter().all(|side| *side == sides[0]));
+        assert_eq!(
+            game.player_count() as usize,
+            game.wonders().iter().map(|wonder| wonder.wonder_type).unique().count()
+        );
+    }
+
+    #[test]
+    fn new_with_wonder_setup_gives_everyone_the_given_side() {
+        let game = Game::new_with_wonder_setup(discarding_algorithms(7), WonderSetup::Side(WonderSide::B), 0).unwrap();
+        assert!(game.wonders().iter().all(|wonder| wonder.wonder_side == WonderSide::B));
+        assert_eq!(
+            game.player_count() as usize,
+            game.wonders().iter().map(|wonder| wonder.wonder_type).unique().count()
+        );
+    }
+
+    #[test]
+    fn new_with_wonder_setup_flips_a_coin_per_player() {
+        let game = Game::new_with_wonder_setup(discarding_algorithms(7), WonderSetup::CoinFlip, 0).unwrap();
+        assert_eq!(7, game.player_count());
+        assert_eq!(
+            game.player_count() as usize,
+            game.wonders().iter().map(|wonder| wonder.wonder_type).unique().count()
+        );
+    }
+
+    #[test]
+    fn new_with_wonder_setup_uses_explicit_wonders() {
+        let wonders = vec![
+            WonderBoard {
+                wonder_type: WonderType::PyramidsOfGiza,
+                wonder_side: WonderSide::B,
+            },
+            WonderBoard {
+                wonder_type: WonderType::ColossusOfRhodes,
+                wonder_side: WonderSide::A,
+            },
+            WonderBoard {
+                wonder_type: WonderType::StatueOfZeus,
+                wonder_side: WonderSide::B,
+            },
+        ];
+        let game =
+            Game::new_with_wonder_setup(discarding_algorithms(3), WonderSetup::Explicit(wonders.clone()), 0).unwrap();
+        assert_eq!(wonders, game.wonders());
+    }
+
+    #[test]
+    fn new_with_wonder_setup_returns_error_if_explicit_wonders_missing() {
+        let wonders = vec![WonderBoard {
+            wonder_type: WonderType::PyramidsOfGiza,
+            wonder_side: WonderSide::B,
+        }];
+        assert!(matches!(
+            Game::new_with_wonder_setup(discarding_algorithms(3), WonderSetup::Explicit(wonders), 0),
+            Err(GameError::WrongNumberOfWonders { players: 3, wonders: 1 })
+        ));
+    }
+
+    #[test]
+    fn new_with_wonder_setup_returns_error_if_explicit_wonders_repeated() {
+        let wonder = WonderBoard {
+            wonder_type: WonderType::PyramidsOfGiza,
+            wonder_side: WonderSide::B,
+        };
+        let result = Game::new_with_wonder_setup(
+            discarding_algorithms(3),
+            WonderSetup::Explicit(vec![wonder, wonder, wonder]),
+            0,
+        );
+        assert!(matches!(
+            result,
+            Err(GameError::DuplicateWonder(WonderType::PyramidsOfGiza))
+        ));
+    }
+
+    #[test]
+    fn new_with_wonder_setup_drafts_wonders() {
+        // Each player picks the last option available, which is the B side of the last remaining wonder.
+        let algorithms: Vec<Box<dyn PlayingAlgorithm>> = vec![
+            Box::new(BuildsWonderStages {}),
+            Box::new(BuildsWonderStages {}),
+            Box::new(BuildsWonderStages {}),
+        ];
+        let game = Game::new_with_wonder_setup(algorithms, WonderSetup::Draft, 0).unwrap();
+        assert_eq!(
+            vec![
+                WonderBoard {
+                    wonder_type: WonderType::PyramidsOfGiza,
+                    wonder_side: WonderSide::B,
+                },
+                WonderBoard {
+                    wonder_type: WonderType::MausoleumOfHalicarnassus,
+                    wonder_side: WonderSide::B,
+                },
+                WonderBoard {
+                    wonder_type: WonderType::StatueOfZeus,
+                    wonder_side: WonderSide::B,
+                },
+            ],
+            game.wonders()
+        );
     }
 
     #[test]
     fn new_game_has_correct_number_of_players() {
         assert_eq!(
             3,
-            Game::new(vec![Box::new(Random {}), Box::new(Random {}), Box::new(Random {})]).player_count()
+            Game::new(
+                vec![
+                    Box::new(Random::default()),
+                    Box::new(Random::default()),
+                    Box::new(Random::default())
+                ],
+                0
+            )
+            .unwrap()
+            .player_count()
         );
     }
 
     #[test]
     fn do_turn_increments_turn() {
-        let mut game = Game::new(vec![Box::new(Random {}), Box::new(Random {}), Box::new(Random {})]);
-        assert_eq!(0, game.turn);
-        game.do_turn();
-        assert_eq!(1, game.turn);
+        let mut game = Game::new(
+            vec![
+                Box::new(Random::default()),
+                Box::new(Random::default()),
+                Box::new(Random::default()),
+            ],
+            0,
+        )
+        .unwrap();
+        assert_eq!(0, game.state.turn);
+        game.do_turn().unwrap();
+        assert_eq!(1, game.state.turn);
     }
 
     #[test]
     fn age_updates_correctly_with_turns() {
-        let mut game = Game::new(vec![Box::new(Random {}), Box::new(Random {}), Box::new(Random {})]);
-        assert_eq!(Age::First, game.age());
+        let mut game = Game::new(
+            vec![
+                Box::new(Random::default()),
+                Box::new(Random::default()),
+                Box::new(Random::default()),
+            ],
+            0,
+        )
+        .unwrap();
+        assert_eq!(Age::First, game.age().unwrap());
         for _i in 0..7 {
-            game.do_turn();
+            game.do_turn().unwrap();
         }
-        assert_eq!(Age::Second, game.age());
+        assert_eq!(Age::Second, game.age().unwrap());
         for _i in 0..7 {
-            game.do_turn();
+            game.do_turn().unwrap();
         }
-        assert_eq!(Age::Third, game.age());
+        assert_eq!(Age::Third, game.age().unwrap());
+    }
+
+    #[test]
+    fn game_state_new_returns_error_if_turn_is_after_the_end_of_the_game() {
+        assert!(matches!(
+            GameState::new(players(3), 18, vec![], vec![], 0),
+            Err(GameError::InvalidTurn(18))
+        ));
+    }
+
+    #[test]
+    fn new_with_state_returns_error_if_algorithm_missing() {
+        let state = GameState::new(players(3), 1, vec![], vec![], 0).unwrap();
+        assert!(matches!(
+            Game::new_with_state(state, discarding_algorithms(2)),
+            Err(GameError::WrongNumberOfAlgorithms {
+                players: 3,
+                algorithms: 2
+            })
+        ));
     }
 
     #[test]
     fn do_turn_deals_new_cards_at_the_start_of_each_age() {
-        let mut game = Game::new(vec![Box::new(Random {}), Box::new(Random {}), Box::new(Random {})]);
-        game.do_turn();
-        assert_eq!(6, game.sentient_players[0].player.hand().len());
+        let mut game = Game::new(
+            vec![
+                Box::new(Random::default()),
+                Box::new(Random::default()),
+                Box::new(Random::default()),
+            ],
+            0,
+        )
+        .unwrap();
+        game.do_turn().unwrap();
+        assert_eq!(6, game.state.players[0].hand().len());
         for _i in 0..6 {
-            game.do_turn();
+            game.do_turn().unwrap();
         }
-        assert_eq!(6, game.sentient_players[0].player.hand().len());
+        assert_eq!(6, game.state.players[0].hand().len());
         for _i in 0..6 {
-            game.do_turn();
+            game.do_turn().unwrap();
         }
-        assert_eq!(6, game.sentient_players[0].player.hand().len());
+        assert_eq!(6, game.state.players[0].hand().len());
     }
 
     #[test]
     fn do_turn_rotates_hands() {
-        let mut game = Game::new(vec![
-            Box::new(AlwaysDiscards {}),
-            Box::new(AlwaysDiscards {}),
-            Box::new(AlwaysDiscards {}),
-        ]);
+        let mut game = Game::new(
+            vec![
+                Box::new(AlwaysDiscards {}),
+                Box::new(AlwaysDiscards {}),
+                Box::new(AlwaysDiscards {}),
+            ],
+            0,
+        )
+        .unwrap();
 
         // We have to do an initial turn so the first age cards are dealt to the players. Before this, nobody has any
         // cards!
-        game.do_turn();
+        game.do_turn().unwrap();
+
+        let player0 = game.state.players[0].hand().clone();
+        let player1 = game.state.players[1].hand().clone();
+        let player2 = game.state.players[2].hand().clone();
 
-        let player0 = game.sentient_players[0].player.hand().clone();
-        let player1 = game.sentient_players[1].player.hand().clone();
-        let player2 = game.sentient_players[2].player.hand().clone();
+        game.do_turn().unwrap();
 
-        game.do_turn();
+        assert_eq!(game.state.players[1].hand()[..], player0[..player0.len() - 1]);
+        assert_eq!(game.state.players[2].hand()[..], player1[..player0.len() - 1]);
+        assert_eq!(game.state.players[0].hand()[..], player2[..player0.len() - 1]);
+    }
 
-        assert_eq!(game.sentient_players[1].player.hand()[..], player0[..player0.len() - 1]);
-        assert_eq!(game.sentient_players[2].player.hand()[..], player1[..player0.len() - 1]);
-        assert_eq!(game.sentient_players[0].player.hand()[..], player2[..player0.len() - 1]);
+    #[test]
+    fn do_turn_resolves_military_conflicts_at_the_end_of_each_age() {
+        let mut game = Game::new(
+            vec![
+                Box::new(AlwaysDiscards {}),
+                Box::new(AlwaysDiscards {}),
+                Box::new(AlwaysDiscards {}),
+            ],
+            0,
+        )
+        .unwrap();
+        game.state.players[0] = Player::new_from_public(
+            &PublicPlayer {
+                wonder: WonderBoard {
+                    wonder_type: WonderType::ColossusOfRhodes,
+                    wonder_side: WonderSide::A,
+                },
+                built_structures: vec![Card::Barracks],
+                wonder_stages_built: 0,
+                military_tokens: vec![],
+                coins: 3,
+                free_build_used: false,
+                copied_guild: None,
+            },
+            vec![],
+        );
+        for _i in 0..5 {
+            game.do_turn().unwrap();
+        }
+        assert!(game.state.players[0].military_tokens().is_empty());
+        game.do_turn().unwrap();
+        assert_eq!(&vec![1, 1], game.state.players[0].military_tokens());
+        assert_eq!(&vec![-1], game.state.players[1].military_tokens());
+        assert_eq!(&vec![-1], game.state.players[2].military_tokens());
     }
 
     #[test]
-    fn get_mutable_player_and_neighbours() {
+    fn do_turn_pays_neighbours_for_borrowing_at_the_end_of_the_turn() {
+        // Player 1 borrows wood from player 2 to build a stockade. Player 2 has no coins, so can't build the timber
+        // yard (costing 1 coin) with the coins they receive until the next turn, and discards it instead.
         let mut players = vec![
-            SentientPlayer {
-                player: Player::new(WonderType::ColossusOfRhodes, WonderSide::A),
-                algorithm: Box::new(Random {}),
+            Player::new(WonderType::ColossusOfRhodes, WonderSide::A),
+            Player::new_from_public(
+                &PublicPlayer {
+                    wonder: WonderBoard {
+                        wonder_type: WonderType::LighthouseOfAlexandria,
+                        wonder_side: WonderSide::A,
+                    },
+                    built_structures: vec![Card::LumberYard],
+                    wonder_stages_built: 0,
+                    military_tokens: vec![],
+                    coins: 0,
+                    free_build_used: false,
+                    copied_guild: None,
+                },
+                vec![],
+            ),
+            Player::new(WonderType::TempleOfArtemis, WonderSide::A),
+        ];
+        players[0].swap_hand(vec![Card::Stockade]);
+        players[1].swap_hand(vec![Card::TimberYard]);
+        players[2].swap_hand(vec![Card::Altar]);
+        let algorithms: Vec<Box<dyn PlayingAlgorithm>> = vec![
+            Box::new(PlaysAction::new(Action::Build(
+                Card::Stockade,
+                Borrowing::new(vec![Borrow::new(Card::LumberYard, Resource::Wood)], vec![]),
+            ))),
+            Box::new(PlaysAction::new(Action::Build(
+                Card::TimberYard,
+                Borrowing::no_borrowing(),
+            ))),
+            Box::new(AlwaysDiscards {}),
+        ];
+        let mut game = game_with_players(players, algorithms, 1);
+
+        game.do_turn().unwrap();
+
+        assert_eq!(vec![Card::Stockade], *game.state.players[0].built_structures());
+        assert_eq!(1, game.state.players[0].coins());
+        assert_eq!(vec![Card::LumberYard], *game.state.players[1].built_structures());
+        assert_eq!(5, game.state.players[1].coins());
+        assert_eq!(
+            RevealedAction {
+                turn: 1,
+                player_index: 0,
+                action: PublicAction::Build(Card::Stockade),
+                payments: vec![CoinTransfer {
+                    from: 0,
+                    to: 1,
+                    coins: 2
+                }],
+                seventh_card: false,
             },
-            SentientPlayer {
-                player: Player::new(WonderType::LighthouseOfAlexandria, WonderSide::A),
-                algorithm: Box::new(Random {}),
+            game.state.history[0]
+        );
+        assert_eq!(PublicAction::Discard, game.state.history[1].action);
+    }
+
+    #[test]
+    fn visible_game_returns_history_for_turn() {
+        let mut game = Game::new(discarding_algorithms(3), 0).unwrap();
+        game.do_turn().unwrap();
+        game.do_turn().unwrap();
+        let public_players = game.state.public_players();
+        let visible_game = VisibleGame {
+            public_players: &public_players,
+            history: &game.state.history,
+            player_index: 0,
+            turn: game.state.turn,
+        };
+        assert_eq!(
+            vec![0, 1, 2],
+            visible_game
+                .history_for_turn(1)
+                .map(|revealed_action| revealed_action.player_index)
+                .collect::<Vec<usize>>()
+        );
+        assert_eq!(0, visible_game.history_for_turn(2).count());
+    }
+
+    #[test]
+    fn do_turn_force_discards_illegal_action() {
+        let mut game = game_with_illegal_action();
+        game.do_turn().unwrap();
+        assert!(game.state.players[0].built_structures().is_empty());
+        assert_eq!(3, game.state.players[0].coins());
+        assert!(game.state.discard_pile.contains(&Card::TimberYard));
+    }
+
+    #[test]
+    fn do_turn_notifies_observers_of_illegal_action() {
+        let mut game = game_with_illegal_action();
+        let events = Rc::new(RefCell::new(vec![]));
+        game.add_observer(Box::new(RecordsEvents { events: events.clone() }));
+        game.do_turn().unwrap();
+        let events = events.borrow();
+        assert_eq!(
+            GameEvent::IllegalActionAttempted {
+                attempt: 1,
+                illegal_action: IllegalAction {
+                    player_index: 0,
+                    turn: 1,
+                    action: Action::Build(Card::TimberYard, Borrowing::no_borrowing()),
+                    reason: IllegalActionReason::CannotAfford,
+                },
+            },
+            events[0]
+        );
+        assert_eq!(
+            GameEvent::ActionRevealed {
+                player_index: 0,
+                action: Action::Discard(Card::TimberYard),
+                seventh_card: false,
             },
-            SentientPlayer {
-                player: Player::new(WonderType::TempleOfArtemis, WonderSide::A),
-                algorithm: Box::new(Random {}),
+            events[1]
+        );
+    }
+
+    #[test]
+    fn do_turn_aborts_on_illegal_action() {
+        let mut game = game_with_illegal_action();
+        game.set_illegal_action_policy(IllegalActionPolicy::Abort);
+        assert_eq!(
+            Err(GameError::IllegalAction(IllegalAction {
+                player_index: 0,
+                turn: 1,
+                action: Action::Build(Card::TimberYard, Borrowing::no_borrowing()),
+                reason: IllegalActionReason::CannotAfford,
+            })),
+            game.do_turn()
+        );
+    }
+
+    #[test]
+    fn do_turn_aborts_after_retrying_illegal_action() {
+        let mut game = game_with_illegal_action();
+        game.set_illegal_action_policy(IllegalActionPolicy::Retry(3));
+        assert!(game.do_turn().is_err());
+    }
+
+    #[test]
+    fn do_turn_retries_illegal_action() {
+        let mut game = game_with_illegal_action();
+        game.algorithms[0] = Box::new(DiscardsWhenToldActionIsIllegal { told: false });
+        game.set_illegal_action_policy(IllegalActionPolicy::Retry(2));
+        game.do_turn().unwrap();
+        assert_eq!(3, game.state.players[0].coins());
+        assert!(game.state.discard_pile.contains(&Card::TimberYard));
+    }
+
+    #[test]
+    fn do_turn_records_illegal_actions_in_state() {
+        let mut game = game_with_illegal_action();
+        game.do_turn().unwrap();
+        assert_eq!(
+            vec![IllegalAction {
+                player_index: 0,
+                turn: 1,
+                action: Action::Build(Card::TimberYard, Borrowing::no_borrowing()),
+                reason: IllegalActionReason::CannotAfford,
+            }],
+            *game.state.illegal_actions()
+        );
+    }
+
+    /// Returns a game on turn 1 where player 1 has no coins and always tries to build a timber yard (costing 1 coin).
+    fn game_with_illegal_action() -> Game {
+        let mut players = players(3);
+        players[0].add_coins(-3);
+        for player in players.iter_mut() {
+            player.swap_hand(vec![Card::TimberYard, Card::Altar]);
+        }
+        let mut algorithms = discarding_algorithms(3);
+        algorithms[0] = Box::new(PlaysAction::new(Action::Build(
+            Card::TimberYard,
+            Borrowing::no_borrowing(),
+        )));
+        game_with_players(players, algorithms, 1)
+    }
+
+    #[test]
+    fn do_turn_discards_final_cards_at_the_end_of_each_age() {
+        let mut game = Game::new(
+            vec![
+                Box::new(AlwaysDiscards {}),
+                Box::new(AlwaysDiscards {}),
+                Box::new(AlwaysDiscards {}),
+            ],
+            0,
+        )
+        .unwrap();
+        for _i in 0..6 {
+            game.do_turn().unwrap();
+        }
+        assert_eq!(21, game.state.discard_pile.len());
+        for player in &game.state.players {
+            assert!(player.hand().is_empty());
+        }
+    }
+
+    #[test]
+    fn do_turn_lets_babylon_play_seventh_card_at_the_end_of_an_age() {
+        let mut players = players(3);
+        // The second stage of the Hanging Gardens of Babylon (side B) lets the player play the seventh card.
+        players[0] = Player::new_from_public(
+            &PublicPlayer {
+                wonder: WonderBoard {
+                    wonder_type: WonderType::HangingGardensOfBabylon,
+                    wonder_side: WonderSide::B,
+                },
+                built_structures: vec![],
+                wonder_stages_built: 2,
+                military_tokens: vec![],
+                coins: 0,
+                free_build_used: false,
+                copied_guild: None,
             },
-            SentientPlayer {
-                player: Player::new(WonderType::HangingGardensOfBabylon, WonderSide::A),
-                algorithm: Box::new(Random {}),
+            vec![Card::Tavern, Card::Altar],
+        );
+        for player in players.iter_mut().skip(1) {
+            player.swap_hand(vec![Card::Tavern, Card::Altar]);
+        }
+        let mut game = game_with_players(players, discarding_algorithms(3), 5);
+
+        game.do_turn().unwrap();
+
+        // Babylon discarded both cards, for 3 coins each. The other players discarded one card and lost the other.
+        assert_eq!(6, game.state.players[0].coins());
+        assert_eq!(6, game.state.players[1].coins());
+        assert_eq!(6, game.state.discard_pile.len());
+        assert!(game.state.players[0].hand().is_empty());
+    }
+
+    #[test]
+    fn do_turn_builds_from_discard_pile_after_building_mausoleum_stage() {
+        let mut game = Game::new(
+            vec![
+                Box::new(BuildsWonderStages {}),
+                Box::new(AlwaysDiscards {}),
+                Box::new(AlwaysDiscards {}),
+            ],
+            0,
+        )
+        .unwrap();
+        // The second stage of the Mausoleum of Halicarnassus (side A) costs 3 ore.
+        game.state.players[0] = Player::new_from_public(
+            &PublicPlayer {
+                wonder: WonderBoard {
+                    wonder_type: WonderType::MausoleumOfHalicarnassus,
+                    wonder_side: WonderSide::A,
+                },
+                built_structures: vec![Card::Foundry, Card::OreVein],
+                wonder_stages_built: 1,
+                military_tokens: vec![],
+                coins: 3,
+                free_build_used: false,
+                copied_guild: None,
             },
-        ];
+            vec![],
+        );
+        game.state.discard_pile.push(Card::Palace);
 
-        let (right, player, left) = Game::get_mutable_player_and_neighbours(&mut players, 0);
-        assert_eq!(WonderType::HangingGardensOfBabylon, right.player.wonder().wonder_type);
-        assert_eq!(WonderType::ColossusOfRhodes, player.player.wonder().wonder_type);
-        assert_eq!(WonderType::LighthouseOfAlexandria, left.player.wonder().wonder_type);
+        game.do_turn().unwrap();
 
-        let (right, player, left) = Game::get_mutable_player_and_neighbours(&mut players, 1);
-        assert_eq!(WonderType::ColossusOfRhodes, right.player.wonder().wonder_type);
-        assert_eq!(WonderType::LighthouseOfAlexandria, player.player.wonder().wonder_type);
-        assert_eq!(WonderType::TempleOfArtemis, left.player.wonder().wonder_type);
+        assert_eq!(2, game.state.players[0].built_wonder_stages().len());
+        assert!(game.state.players[0].built_structures().contains(&Card::Palace));
+        assert!(!game.state.discard_pile.contains(&Card::Palace));
+        assert_eq!(2, game.state.discard_pile.len());
+    }
 
-        let (right, player, left) = Game::get_mutable_player_and_neighbours(&mut players, 3);
-        assert_eq!(WonderType::TempleOfArtemis, right.player.wonder().wonder_type);
-        assert_eq!(WonderType::HangingGardensOfBabylon, player.player.wonder().wonder_type);
-        assert_eq!(WonderType::ColossusOfRhodes, left.player.wonder().wonder_type);
+    #[test]
+    fn play_copies_best_neighbour_guild_for_olympia_b() {
+        let mut players = players(3);
+        players[0] = Player::new_from_public(
+            &PublicPlayer {
+                wonder: WonderBoard {
+                    wonder_type: WonderType::StatueOfZeus,
+                    wonder_side: WonderSide::B,
+                },
+                built_structures: vec![],
+                wonder_stages_built: 3,
+                military_tokens: vec![],
+                coins: 0,
+                free_build_used: false,
+                copied_guild: None,
+            },
+            vec![Card::Tavern],
+        );
+        players[1] = Player::new_from_public(
+            &PublicPlayer {
+                wonder: WonderBoard {
+                    wonder_type: WonderType::ColossusOfRhodes,
+                    wonder_side: WonderSide::A,
+                },
+                built_structures: vec![
+                    Card::WorkersGuild,
+                    Card::ScientistsGuild,
+                    Card::LumberYard,
+                    Card::OreVein,
+                ],
+                wonder_stages_built: 0,
+                military_tokens: vec![],
+                coins: 0,
+                free_build_used: false,
+                copied_guild: None,
+            },
+            vec![Card::Altar],
+        );
+        players[2].swap_hand(vec![Card::Baths]);
+        let mut game = game_with_players(players, discarding_algorithms(3), 17);
+
+        let result = game.play().unwrap();
+
+        assert_eq!(Some(Card::WorkersGuild), game.state.players[0].copied_guild());
+        assert_eq!(2, result.scores[0].guilds);
+        assert_eq!(None, game.state.players[1].copied_guild());
     }
 
     #[test]
     fn play_returns_scores() {
         assert_eq!(
             3,
-            Game::new(vec![Box::new(Random {}), Box::new(Random {}), Box::new(Random {})])
-                .play()
-                .len()
+            Game::new(
+                vec![
+                    Box::new(Random::default()),
+                    Box::new(Random::default()),
+                    Box::new(Random::default())
+                ],
+                0
+            )
+            .unwrap()
+            .play()
+            .unwrap()
+            .scores
+            .len()
+        );
+    }
+
+    #[test]
+    fn play_notifies_observers_of_events() {
+        let mut game = Game::new(discarding_algorithms(3), 0).unwrap();
+        let events = Rc::new(RefCell::new(vec![]));
+        game.add_observer(Box::new(RecordsEvents { events: events.clone() }));
+        let result = game.play().unwrap();
+
+        let events = events.borrow();
+        assert_eq!(GameEvent::AgeStarted(Age::First), events[0]);
+        for (player_index, event) in events[1..4].iter().enumerate() {
+            let dealt = |i: &usize, hand: &Vec<Card>| *i == player_index && hand.len() == 7;
+            assert!(matches!(event, GameEvent::CardsDealt { player_index: i, hand } if dealt(i, hand)));
+        }
+        let count = |predicate: fn(&GameEvent) -> bool| events.iter().filter(|event| predicate(event)).count();
+        assert_eq!(3, count(|event| matches!(event, GameEvent::AgeStarted(_))));
+        assert_eq!(54, count(|event| matches!(event, GameEvent::ActionRevealed { .. })));
+        assert_eq!(3, count(|event| matches!(event, GameEvent::ConflictResolved { .. })));
+        assert_eq!(Some(&GameEvent::GameEnded(result)), events.last());
+    }
+
+    #[test]
+    fn play_gives_identical_games_for_the_same_seed() {
+        let play = |seed| {
+            let algorithms: Vec<Box<dyn PlayingAlgorithm>> = vec![
+                Box::new(Random::default()),
+                Box::new(Random::default()),
+                Box::new(Random::default()),
+                Box::new(Random::default()),
+            ];
+            let mut game = Game::new_with_wonder_setup(algorithms, WonderSetup::Draft, seed).unwrap();
+            (game.wonders(), game.play().unwrap())
+        };
+        assert_eq!(play(42), play(42));
+    }
+
+    #[test]
+    fn game_state_new_deals_at_the_start_of_an_age_only_if_nobody_holds_cards() {
+        let state = GameState::new(players(3), 6, vec![], vec![], 0).unwrap();
+        assert_eq!(&Step::Deal, state.step());
+
+        let mut players = players(3);
+        for player in players.iter_mut() {
+            player.swap_hand(vec![Card::Sawmill]);
+        }
+        let state = GameState::new(players, 6, vec![], vec![], 0).unwrap();
+        assert_eq!(
+            &Step::Actions {
+                player_indexes: vec![0, 1, 2],
+                seventh_card: false
+            },
+            state.step()
+        );
+    }
+
+    #[test]
+    fn game_state_apply_moves_on_to_the_next_turn() {
+        let mut state = GameState::new(players(3), 0, vec![], vec![], 0).unwrap();
+        state.deal().unwrap();
+        let actions = state
+            .players()
+            .iter()
+            .map(|player| Action::Discard(player.hand()[0]))
+            .collect();
+
+        let events = state.apply(actions).unwrap();
+
+        assert_eq!(1, state.turn());
+        assert_eq!(3, state.discard_pile().len());
+        assert_eq!(3, state.history().len());
+        assert_eq!(
+            3,
+            events
+                .iter()
+                .filter(|event| matches!(event, GameEvent::ActionRevealed { .. }))
+                .count()
+        );
+        assert!(matches!(state.step(), Step::Actions { player_indexes, .. } if player_indexes.len() == 3));
+    }
+
+    #[test]
+    fn game_state_apply_leaves_state_unchanged_if_an_action_is_illegal() {
+        let mut state = game_with_illegal_action().state;
+        let actions = vec![
+            Action::Discard(Card::Altar),
+            Action::Discard(Card::Altar),
+            Action::Build(Card::Altar, Borrowing::no_borrowing()),
+        ];
+        let actions_with_illegal = vec![
+            Action::Build(Card::TimberYard, Borrowing::no_borrowing()),
+            Action::Discard(Card::Altar),
+            Action::Discard(Card::Altar),
+        ];
+
+        assert!(matches!(
+            state.apply(actions_with_illegal),
+            Err(GameError::IllegalAction(IllegalAction { player_index: 0, .. }))
+        ));
+        assert!(state.discard_pile().is_empty());
+        assert!(state.history().is_empty());
+
+        state.apply(actions).unwrap();
+        assert_eq!(2, state.discard_pile().len());
+        assert_eq!(&vec![Card::Altar], state.players()[2].built_structures());
+    }
+
+    #[test]
+    fn game_state_returns_error_for_unexpected_step() {
+        let mut state = GameState::new(players(3), 0, vec![], vec![], 0).unwrap();
+        assert_eq!(Err(GameError::UnexpectedStep), state.apply(vec![]));
+        assert_eq!(Err(GameError::UnexpectedStep), state.build_from_discard(None));
+        state.deal().unwrap();
+        assert_eq!(Err(GameError::UnexpectedStep), state.deal());
+        assert_eq!(
+            Err(GameError::WrongNumberOfActions { expected: 3, actual: 0 }),
+            state.apply(vec![])
         );
     }
 
+    #[test]
+    fn game_state_waits_for_discard_build_after_building_mausoleum_stage() {
+        let mut players = players(3);
+        // The second stage of the Mausoleum of Halicarnassus (side A) costs 3 ore.
+        players[0] = Player::new_from_public(
+            &PublicPlayer {
+                wonder: WonderBoard {
+                    wonder_type: WonderType::MausoleumOfHalicarnassus,
+                    wonder_side: WonderSide::A,
+                },
+                built_structures: vec![Card::Foundry, Card::OreVein],
+                wonder_stages_built: 1,
+                military_tokens: vec![],
+                coins: 3,
+                free_build_used: false,
+                copied_guild: None,
+            },
+            vec![Card::Altar],
+        );
+        players[1].swap_hand(vec![Card::Baths]);
+        players[2].swap_hand(vec![Card::Theater]);
+        let mut state = GameState::new(players, 1, vec![Card::Palace], vec![], 0).unwrap();
+
+        state
+            .apply(vec![
+                Action::Wonder(Card::Altar, Borrowing::no_borrowing()),
+                Action::Discard(Card::Baths),
+                Action::Discard(Card::Theater),
+            ])
+            .unwrap();
+        assert_eq!(1, state.turn());
+        assert_eq!(
+            &Step::DiscardBuild {
+                player_index: 0,
+                cards: vec![Card::Palace, Card::Baths, Card::Theater]
+            },
+            state.step()
+        );
+
+        let events = state.build_from_discard(Some(Card::Palace)).unwrap();
+        assert_eq!(
+            vec![GameEvent::BuiltFromDiscard {
+                player_index: 0,
+                card: Card::Palace
+            }],
+            events
+        );
+        assert_eq!(2, state.turn());
+    }
+
+    #[test]
+    fn game_state_skips_to_seventh_card() {
+        let mut players = players(3);
+        // The second stage of the Hanging Gardens of Babylon (side B) lets the player play the seventh card.
+        players[0] = Player::new_from_public(
+            &PublicPlayer {
+                wonder: WonderBoard {
+                    wonder_type: WonderType::HangingGardensOfBabylon,
+                    wonder_side: WonderSide::B,
+                },
+                built_structures: vec![],
+                wonder_stages_built: 2,
+                military_tokens: vec![],
+                coins: 0,
+                free_build_used: false,
+                copied_guild: None,
+            },
+            vec![Card::Tavern],
+        );
+        for player in players.iter_mut().skip(1) {
+            player.swap_hand(vec![Card::Altar]);
+        }
+        let mut state = GameState::new(players.clone(), 4, vec![], vec![], 0).unwrap();
+        assert_eq!(Err(GameError::UnexpectedStep), state.skip_to_seventh_card());
+
+        let mut state = GameState::new(players, 5, vec![], vec![], 0).unwrap();
+        state.skip_to_seventh_card().unwrap();
+        assert_eq!(
+            &Step::Actions {
+                player_indexes: vec![0],
+                seventh_card: true
+            },
+            state.step()
+        );
+
+        state.apply(vec![Action::Discard(Card::Tavern)]).unwrap();
+        assert_eq!(6, state.turn());
+        assert_eq!(3, state.players[0].coins());
+        assert_eq!(&Step::Deal, state.step());
+    }
+
+    #[test]
+    fn game_state_clone_can_be_played_on_independently() {
+        let mut game = Game::new(discarding_algorithms(3), 0).unwrap();
+        game.do_turn().unwrap();
+        let state = game.state().clone();
+
+        let mut fork = Game::new_with_state(state.clone(), discarding_algorithms(3)).unwrap();
+        fork.do_turn().unwrap();
+        game.do_turn().unwrap();
+
+        assert_eq!(1, state.turn());
+        assert_eq!(2, fork.state().turn());
+        assert_eq!(game.state().history(), fork.state().history());
+        assert_eq!(game.state().players()[0].hand(), fork.state().players()[0].hand());
+        assert_eq!(game.play().unwrap(), fork.play().unwrap());
+    }
+
+    #[test]
+    fn game_result_ranks_players_by_score() {
+        let result = GameResult::new(vec![score(10), score(30), score(20)], vec![0, 0, 0]);
+        assert_eq!(vec![vec![1], vec![2], vec![0]], result.rankings);
+        assert_eq!(&vec![1], result.winners());
+    }
+
+    #[test]
+    fn game_result_breaks_ties_with_coins() {
+        let result = GameResult::new(vec![score(30), score(30), score(20)], vec![2, 5, 9]);
+        assert_eq!(vec![vec![1], vec![0], vec![2]], result.rankings);
+        assert!(result.is_winner(1));
+        assert!(!result.is_winner(0));
+    }
+
+    #[test]
+    fn game_result_shares_positions_if_still_tied() {
+        let result = GameResult::new(vec![score(30), score(20), score(30), score(20)], vec![4, 1, 4, 1]);
+        assert_eq!(vec![vec![0, 2], vec![1, 3]], result.rankings);
+        assert!(result.is_winner(0));
+        assert!(result.is_winner(2));
+    }
+
+    fn score(civilian: i32) -> ScoreBreakdown {
+        ScoreBreakdown {
+            civilian,
+            ..Default::default()
+        }
+    }
+
     /// Always discards the last card in the hand.
     #[derive(Debug)]
     pub struct AlwaysDiscards;
     impl PlayingAlgorithm for AlwaysDiscards {
+        fn name(&self) -> &'static str {
+            "AlwaysDiscards"
+        }
+
         fn get_next_action(&mut self, player: &Player, _visible_game: &VisibleGame) -> Action {
             // TODO: we always discard the last card so the order of the hand is not disrupted (because
             //  player::do_action uses Vec::swap_remove). Ideally don't rely on the implementation of do_action. But
@@ -410,4 +2009,119 @@ mod tests {
             Action::Discard(player.hand()[player.hand().len() - 1])
         }
     }
+
+    /// Always builds a wonder stage with the last card in the hand, if possible, and otherwise discards it. Builds
+    /// the palace from the discard pile when allowed, and otherwise the first card offered.
+    #[derive(Debug)]
+    pub struct BuildsWonderStages;
+    impl PlayingAlgorithm for BuildsWonderStages {
+        fn name(&self) -> &'static str {
+            "BuildsWonderStages"
+        }
+
+        fn get_next_action(&mut self, player: &Player, visible_game: &VisibleGame) -> Action {
+            let card = player.hand()[player.hand().len() - 1];
+            let action = Action::Wonder(card, Borrowing::no_borrowing());
+            if player.can_play(&action, visible_game) {
+                action
+            } else {
+                Action::Discard(card)
+            }
+        }
+
+        fn get_discard_build(&mut self, _player: &Player, _visible_game: &VisibleGame, cards: &[Card]) -> Option<Card> {
+            cards
+                .iter()
+                .find(|card| **card == Card::Palace)
+                .or_else(|| cards.first())
+                .copied()
+        }
+
+        fn choose_wonder(&mut self, wonders: &[WonderBoard]) -> WonderBoard {
+            wonders[wonders.len() - 1]
+        }
+    }
+
+    /// Always returns the given action, whether or not it is legal.
+    #[derive(Debug)]
+    pub struct PlaysAction {
+        action: Action,
+    }
+
+    impl PlaysAction {
+        fn new(action: Action) -> PlaysAction {
+            PlaysAction { action }
+        }
+    }
+
+    impl PlayingAlgorithm for PlaysAction {
+        fn name(&self) -> &'static str {
+            "PlaysAction"
+        }
+
+        fn get_next_action(&mut self, _player: &Player, _visible_game: &VisibleGame) -> Action {
+            self.action.clone()
+        }
+    }
+
+    /// Tries to build a timber yard until told the action is illegal, and then discards it.
+    #[derive(Debug)]
+    pub struct DiscardsWhenToldActionIsIllegal {
+        told: bool,
+    }
+
+    impl PlayingAlgorithm for DiscardsWhenToldActionIsIllegal {
+        fn name(&self) -> &'static str {
+            "DiscardsWhenToldActionIsIllegal"
+        }
+
+        fn get_next_action(&mut self, _player: &Player, _visible_game: &VisibleGame) -> Action {
+            if self.told {
+                Action::Discard(Card::TimberYard)
+            } else {
+                Action::Build(Card::TimberYard, Borrowing::no_borrowing())
+            }
+        }
+
+        fn illegal_action(&mut self, _action: &Action, _reason: IllegalActionReason) {
+            self.told = true;
+        }
+    }
+
+    /// Records every event it's told about.
+    #[derive(Debug)]
+    struct RecordsEvents {
+        events: Rc<RefCell<Vec<GameEvent>>>,
+    }
+
+    impl GameObserver for RecordsEvents {
+        fn on_event(&mut self, event: &GameEvent) {
+            self.events.borrow_mut().push(event.clone());
+        }
+    }
+
+    fn discarding_algorithms(count: usize) -> Vec<Box<dyn PlayingAlgorithm>> {
+        (0..count)
+            .map(|_| Box::new(AlwaysDiscards {}) as Box<dyn PlayingAlgorithm>)
+            .collect()
+    }
+
+    /// Returns the given number of players, all with the same wonder and no cards.
+    fn players(count: usize) -> Vec<Player> {
+        (0..count)
+            .map(|_| Player::new(WonderType::ColossusOfRhodes, WonderSide::A))
+            .collect()
+    }
+
+    /// Returns a game starting from the given turn, with the given players (already holding their hands for the turn)
+    /// and algorithms.
+    fn game_with_players(players: Vec<Player>, algorithms: Vec<Box<dyn PlayingAlgorithm>>, turn: u32) -> Game {
+        Game::new_with_state(GameState::new(players, turn, vec![], vec![], 0).unwrap(), algorithms).unwrap()
+    }
+
+    impl Game {
+        fn wonders(&self) -> Vec<WonderBoard> {
+            self.state.players.iter().map(|player| *player.wonder()).collect()
+        }
+    }
 }
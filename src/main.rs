@@ -1,35 +1,129 @@
+// The tests compare booleans with assert_eq! for consistency with the other assertions.
+#![cfg_attr(test, allow(clippy::bool_assert_comparison))]
+
 use crate::algorithms::human::Human;
 use crate::algorithms::monte_carlo::MonteCarlo;
 use crate::algorithms::random::Random;
+use crate::algorithms::PlayingAlgorithm;
 use crate::game::Game;
+use crate::observer::ConsoleObserver;
 use crate::utils::plural;
 use itertools::Itertools;
+use rand::{thread_rng, Rng};
+use std::{env, fs};
 
 mod action;
 mod algorithms;
 mod card;
+mod error;
 mod game;
+mod observer;
 mod player;
 mod power;
 mod resources;
+mod save;
+mod score;
 mod table;
 mod utils;
 mod wonder;
 
+/// The command line arguments: `[<seed>] [--save <file>]`, or `--load <file> [--save <file>]`.
+#[derive(Default)]
+struct Args {
+    /// The seed to play a new game from. The game can be replayed exactly by passing the seed it printed.
+    seed: Option<u64>,
+    /// A file written by `--save` to carry on playing from, instead of starting a new game.
+    load: Option<String>,
+    /// A file to save the game to at the start of every turn, so it can be carried on later using `--load`.
+    save: Option<String>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--load" => parsed.load = Some(args.next().ok_or("Missing file to load")?),
+                "--save" => parsed.save = Some(args.next().ok_or("Missing file to save to")?),
+                _ => parsed.seed = Some(arg.parse().map_err(|_| format!("Invalid seed: {}", arg))?),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
 fn main() {
-    let mut game = Game::new(vec![Box::new(Human {}), Box::new(MonteCarlo {}), Box::new(Random {})]);
-    let scores = game.play();
-    let sorted_scores: Vec<(usize, i32)> = scores
-        .into_iter()
-        .enumerate()
-        .sorted_by_key(|(_, score)| *score)
-        .rev()
-        .collect();
-
-    // TODO: deal with draws
-    println!("Player {} wins!", sorted_scores[0].0 + 1);
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            println!("{}", message);
+            return;
+        }
+    };
+
+    let game = match &args.load {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|error| format!("Couldn't read {}: {}", path, error))
+            .and_then(|text| save::load(&text).map_err(|error| format!("Couldn't load {}: {}", path, error))),
+        None => {
+            let seed = args.seed.unwrap_or_else(|| thread_rng().gen());
+            println!("Seed: {}", seed);
+            let algorithms: Vec<Box<dyn PlayingAlgorithm>> = vec![
+                Box::new(Human {}),
+                Box::new(MonteCarlo::default()),
+                Box::new(Random::default()),
+            ];
+            Game::new(algorithms, seed).map_err(|error| format!("Game aborted. {}", error))
+        }
+    };
+    let mut game = match game {
+        Ok(game) => game,
+        Err(message) => {
+            println!("{}", message);
+            return;
+        }
+    };
+    game.add_observer(Box::new(ConsoleObserver {}));
+
+    let result = loop {
+        if let Some(path) = &args.save {
+            let saved = save::save(&game)
+                .map_err(|error| error.to_string())
+                .and_then(|text| fs::write(path, text).map_err(|error| error.to_string()));
+            if let Err(error) = saved {
+                println!("Couldn't save to {}: {}", path, error);
+                return;
+            }
+        }
+        match game.play_turn() {
+            Ok(Some(result)) => break result,
+            Ok(None) => {}
+            Err(error) => {
+                println!("Game aborted. {}", error);
+                return;
+            }
+        }
+    };
+
+    let winners = result.winners();
+    if winners.len() == 1 {
+        println!("Player {} wins!", winners[0] + 1);
+    } else {
+        println!(
+            "Players {} share the victory!",
+            winners.iter().map(|index| index + 1).format(" and ")
+        );
+    }
     println!();
-    for (i, score) in sorted_scores {
-        println!("Player {}: {}", i + 1, plural(score, "point"));
+    for (position, players) in result.rankings.iter().enumerate() {
+        for i in players {
+            println!(
+                "{}. Player {}: {}, {}",
+                position + 1,
+                i + 1,
+                result.scores[*i],
+                plural(result.coins[*i], "coin")
+            );
+        }
     }
 }
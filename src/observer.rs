@@ -0,0 +1,117 @@
+//! Lets callers observe what happens during a game, for example to write it to the console, log it or collect
+//! statistics.
+
+use std::fmt::Debug;
+
+use itertools::Itertools;
+
+use crate::action::{Action, CoinTransfer};
+use crate::card::{Age, Card};
+use crate::game::{GameResult, IllegalAction};
+use crate::utils::plural;
+
+/// Something that happened during a game. Player indexes are as for the players in the game. Events can include
+/// private information, such as the cards in each player's hand.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum GameEvent {
+    /// A new age started.
+    AgeStarted(Age),
+    /// The player was dealt a new hand at the start of an age.
+    CardsDealt { player_index: usize, hand: Vec<Card> },
+    /// A playing algorithm returned an illegal action. `attempt` counts the illegal actions returned by the player this
+    /// turn, starting from 1.
+    IllegalActionAttempted {
+        attempt: u32,
+        illegal_action: IllegalAction,
+    },
+    /// The action the player performed was revealed. `seventh_card` is `true` if this was an extra action taken with
+    /// the final card of an age (ie. on the Hanging Gardens of Babylon).
+    ActionRevealed {
+        player_index: usize,
+        action: Action,
+        seventh_card: bool,
+    },
+    /// Coins paid by one player to a neighbour, for borrowing resources, were credited to the neighbour.
+    CoinsTransferred(CoinTransfer),
+    /// The player built a card from the discard pile for free (ie. on the Mausoleum of Halicarnassus).
+    BuiltFromDiscard { player_index: usize, card: Card },
+    /// The player copied a neighbour's guild for final scoring (ie. on the Statue of Zeus in Olympia, side B).
+    GuildCopied { player_index: usize, guild: Card },
+    /// The military conflicts at the end of an age were resolved. `shields` and `military_tokens` hold each player's
+    /// shields and all the military tokens they hold after the conflicts.
+    ConflictResolved {
+        age: Age,
+        shields: Vec<u32>,
+        military_tokens: Vec<Vec<i32>>,
+    },
+    /// The game ended with the given result.
+    GameEnded(GameResult),
+}
+
+/// Something that wants to be told about the events happening during a game. See [`crate::game::Game::add_observer`].
+pub trait GameObserver: Debug {
+    /// Called for each event, in the order the events happen.
+    fn on_event(&mut self, event: &GameEvent);
+}
+
+/// Writes a description of the public events of a game to the console.
+#[derive(Debug)]
+pub struct ConsoleObserver;
+
+impl GameObserver for ConsoleObserver {
+    fn on_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::IllegalActionAttempted {
+                attempt,
+                illegal_action,
+            } => {
+                // Print illegal actions in full (even though the action may include private information), so buggy
+                // algorithms can be diagnosed.
+                println!(
+                    "Player {}: Illegal action (attempt {}, {}): {:?}",
+                    illegal_action.player_index + 1,
+                    attempt,
+                    illegal_action.reason,
+                    illegal_action.action
+                );
+            }
+            GameEvent::ActionRevealed {
+                player_index,
+                action,
+                seventh_card,
+            } => {
+                if *seventh_card {
+                    println!("Player {}: {} (seventh card)", player_index + 1, action);
+                } else {
+                    println!("Player {}: {}", player_index + 1, action);
+                }
+            }
+            GameEvent::BuiltFromDiscard { player_index, card } => {
+                println!("Player {}: Build {} from the discard pile", player_index + 1, card);
+            }
+            GameEvent::GuildCopied { player_index, guild } => {
+                println!("Player {}: Copy {} from a neighbour", player_index + 1, guild);
+            }
+            GameEvent::ConflictResolved {
+                age,
+                shields,
+                military_tokens,
+            } => {
+                println!("Military conflicts at the end of the {:?} age:", age);
+                for (index, (shields, military_tokens)) in shields.iter().zip(military_tokens).enumerate() {
+                    println!(
+                        "Player {}: {}, military tokens {}",
+                        index + 1,
+                        plural(*shields as i32, "shield"),
+                        military_tokens.iter().format(", ")
+                    );
+                }
+            }
+            // Hands are private, coin transfers are implied by the actions, and the caller reports the final result.
+            GameEvent::AgeStarted(_)
+            | GameEvent::CardsDealt { .. }
+            | GameEvent::CoinsTransferred(_)
+            | GameEvent::GameEnded(_) => {}
+        }
+    }
+}
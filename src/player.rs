@@ -1,24 +1,35 @@
+use itertools::Itertools;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
-use std::collections::HashMap;
+use rand_chacha::ChaCha8Rng;
 use std::fmt::Debug;
 use std::mem;
 
-use crate::action::{Action, ActionOptions, Borrow, Borrowing};
-use crate::card::{Card, Colour};
-use crate::game::VisibleGame;
-use crate::power::ScienceItem;
+use crate::action::{Action, ActionOptions, Borrow, Borrowing, CoinTransfer, IllegalActionReason};
+use crate::card::{Age, Card, Colour};
+use crate::error::GameError;
+use crate::game::{IllegalAction, VisibleGame};
+use crate::power::{CountableGameItem, ScienceItem};
 use crate::power::{Power, ProducedResources};
 use crate::resources::{Cost, Resource};
+use crate::score::ScoreBreakdown;
 use crate::wonder::{WonderBoard, WonderSide, WonderType};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Player {
     wonder: WonderBoard,
     built_structures: Vec<Card>,
-    built_wonder_stages: Vec<Option<Card>>, // TODO: how to represent this?
+    /// The card tucked under each built wonder stage, in the order the stages were built. `None` if the card is not
+    /// known, for example when the player was created from a [`PublicPlayer`].
+    built_wonder_stages: Vec<Option<Card>>,
+    /// The military tokens won and lost in conflicts with neighbours at the end of each age. Victory tokens are worth
+    /// 1, 3 or 5 points depending on the age, defeat tokens are worth -1.
+    military_tokens: Vec<i32>,
     coins: i32,
     hand: Vec<Card>,
+    /// Whether the player has used their free build this age (see [`Power::FreeBuildOncePerAge`]).
+    free_build_used: bool,
+    /// The neighbour's guild this player copies during final scoring (see [`Power::CopyGuild`]), if any.
+    copied_guild: Option<Card>,
 }
 
 #[allow(dead_code)]
@@ -31,8 +42,11 @@ impl Player {
             },
             built_structures: vec![],
             built_wonder_stages: vec![],
+            military_tokens: vec![],
             coins: 3,
             hand: vec![],
+            free_build_used: false,
+            copied_guild: None,
         }
     }
 
@@ -41,9 +55,36 @@ impl Player {
         Player {
             wonder: public_player.wonder,
             built_structures: public_player.built_structures.clone(),
-            built_wonder_stages: vec![],
+            built_wonder_stages: vec![None; public_player.wonder_stages_built as usize],
+            military_tokens: public_player.military_tokens.clone(),
             coins: public_player.coins,
             hand,
+            free_build_used: public_player.free_build_used,
+            copied_guild: public_player.copied_guild,
+        }
+    }
+
+    /// Creates a player with the given state. Intended for restoring a saved game (see [`crate::save`]).
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_from_state(
+        wonder: WonderBoard,
+        built_structures: Vec<Card>,
+        built_wonder_stages: Vec<Option<Card>>,
+        military_tokens: Vec<i32>,
+        coins: i32,
+        hand: Vec<Card>,
+        free_build_used: bool,
+        copied_guild: Option<Card>,
+    ) -> Player {
+        Player {
+            wonder,
+            built_structures,
+            built_wonder_stages,
+            military_tokens,
+            coins,
+            hand,
+            free_build_used,
+            copied_guild,
         }
     }
 
@@ -55,6 +96,14 @@ impl Player {
         &self.built_structures
     }
 
+    pub fn built_wonder_stages(&self) -> &Vec<Option<Card>> {
+        &self.built_wonder_stages
+    }
+
+    pub fn military_tokens(&self) -> &Vec<i32> {
+        &self.military_tokens
+    }
+
     pub fn coins(&self) -> i32 {
         self.coins
     }
@@ -64,146 +113,454 @@ impl Player {
     }
 
     /// Performs the given [`Action`] on the current player, for example moving a card from the player's hand into the
-    /// player's built structures. Returns `true` if the action is legal, `false` otherwise (in which case this function
-    /// otherwise does nothing).
+    /// player's built structures. Returns a [`GameError::IllegalAction`] if the action is not legal (in which case this
+    /// function otherwise does nothing).
     ///
-    /// `left_player` and `right_player` are needed in case we need to borrow resources from them, in which case, they
-    /// will have the cost credited to them.
+    /// If resources are borrowed from neighbours, the player pays for them immediately and a [`CoinTransfer`] for each
+    /// neighbour is added to `coin_transfers`. The neighbours are credited when the transfers are settled at the end of
+    /// the turn.
     pub fn do_action(
         &mut self,
         action: &Action,
         visible_game: &VisibleGame,
-        left_player: &mut Player,
-        right_player: &mut Player,
         discard_pile: &mut Vec<Card>,
-    ) -> bool {
+        coin_transfers: &mut Vec<CoinTransfer>,
+    ) -> Result<(), GameError> {
         // Removes and returns the given card from the player's hand.
-        fn remove_from_hand(hand: &mut Vec<Card>, card: &Card) -> Card {
-            let index = hand.iter().position(|c| c == card).unwrap();
-            hand.swap_remove(index)
+        fn remove_from_hand(hand: &mut Vec<Card>, card: &Card) -> Result<Card, GameError> {
+            let index = hand
+                .iter()
+                .position(|c| c == card)
+                .ok_or(GameError::CardNotInHand(*card))?;
+            Ok(hand.swap_remove(index))
+        }
+
+        // Pays neighbours for any resources borrowed from them.
+        fn pay_for_borrowing(
+            player: &mut Player,
+            borrowing: &Borrowing,
+            visible_game: &VisibleGame,
+            coin_transfers: &mut Vec<CoinTransfer>,
+        ) {
+            let (left_coins, right_coins) = player.borrowing_costs(borrowing);
+            player.coins -= left_coins + right_coins;
+            for (to, coins) in [
+                (visible_game.left_neighbour_index(), left_coins),
+                (visible_game.right_neighbour_index(), right_coins),
+            ] {
+                if coins > 0 {
+                    coin_transfers.push(CoinTransfer {
+                        from: visible_game.player_index,
+                        to,
+                        coins,
+                    });
+                }
+            }
         }
 
-        if self.can_play(action, visible_game) {
-            match action {
-                Action::Build(card, borrowing) => {
-                    let card_from_hand = remove_from_hand(&mut self.hand, card);
-                    self.built_structures.push(card_from_hand);
+        if let Err(reason) = self.check_action(action, visible_game) {
+            return Err(GameError::IllegalAction(IllegalAction {
+                player_index: visible_game.player_index,
+                turn: visible_game.turn,
+                action: action.clone(),
+                reason,
+            }));
+        }
+
+        match action {
+            Action::Build(card, borrowing) => {
+                let chained = self.chained_from(card).is_some();
+                let card_from_hand = remove_from_hand(&mut self.hand, card)?;
+                self.built_structures.push(card_from_hand);
+                if !chained {
                     self.coins -= card_from_hand.cost().coins;
-                    // TODO: cost of borrowing needs to vary depending on yellow cards.
-                    self.coins -= borrowing.left.len() as i32 * 2 + borrowing.right.len() as i32 * 2;
-                    left_player.add_coins(borrowing.left.len() as i32 * 2);
-                    right_player.add_coins(borrowing.right.len() as i32 * 2);
+                    pay_for_borrowing(self, borrowing, visible_game, coin_transfers);
                 }
-                Action::Wonder(_, _) => todo!(),
-                Action::Discard(card) => {
-                    discard_pile.push(remove_from_hand(&mut self.hand, card));
-                    self.coins += 3;
+                self.coins += self.immediate_coins(card_from_hand.power(), visible_game);
+            }
+            Action::Wonder(card, borrowing) => {
+                let position = self.built_wonder_stages.len() as u32;
+                let cost = self.wonder.cost(position)?.clone();
+                let card_from_hand = remove_from_hand(&mut self.hand, card)?;
+                self.built_wonder_stages.push(Some(card_from_hand));
+                self.coins -= cost.coins;
+                pay_for_borrowing(self, borrowing, visible_game, coin_transfers);
+                for power in self.wonder.powers(position)? {
+                    self.coins += self.immediate_coins(power, visible_game);
                 }
             }
+            Action::FreeBuild(card) => {
+                let card_from_hand = remove_from_hand(&mut self.hand, card)?;
+                self.built_structures.push(card_from_hand);
+                self.free_build_used = true;
+                self.coins += self.immediate_coins(card_from_hand.power(), visible_game);
+            }
+            Action::Discard(card) => {
+                discard_pile.push(remove_from_hand(&mut self.hand, card)?);
+                self.coins += 3;
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds the given card, taken from the discard pile, for free. Returns `true` if the card was built, or `false`
+    /// if the player has already built the same structure (in which case this function otherwise does nothing).
+    pub fn build_from_discard(&mut self, card: &Card, visible_game: &VisibleGame) -> bool {
+        if self.has_built(card) {
+            return false;
+        }
+        self.built_structures.push(*card);
+        self.coins += self.immediate_coins(card.power(), visible_game);
+        true
+    }
+
+    /// Returns `true` if this player can build a structure for free this age, because they have the
+    /// [`Power::FreeBuildOncePerAge`] power and haven't yet used it this age.
+    pub fn free_build_available(&self) -> bool {
+        !self.free_build_used && self.powers().any(|power| matches!(power, Power::FreeBuildOncePerAge))
+    }
+
+    pub fn free_build_used(&self) -> bool {
+        self.free_build_used
+    }
+
+    /// Makes the free build available again. Called at the start of each age.
+    pub fn reset_free_build(&mut self) {
+        self.free_build_used = false;
+    }
+
+    /// Returns the guilds built by this player's neighbours that this player could copy during final scoring. Empty if
+    /// the player doesn't have the [`Power::CopyGuild`] power.
+    pub fn guilds_to_copy(&self, left: &PublicPlayer, right: &PublicPlayer) -> Vec<Card> {
+        if !self.powers().any(|power| matches!(power, Power::CopyGuild)) {
+            return vec![];
+        }
+        left.built_structures
+            .iter()
+            .chain(right.built_structures.iter())
+            .filter(|card| *card.colour() == Colour::Purple && !self.has_built(card))
+            .copied()
+            .unique()
+            .collect()
+    }
+
+    /// Returns the guild from `guilds` (usually [`Player::guilds_to_copy`]) that gives this player the highest score,
+    /// or `None` if `guilds` is empty.
+    pub fn best_guild_to_copy(&self, guilds: &[Card], left: &PublicPlayer, right: &PublicPlayer) -> Option<Card> {
+        guilds.iter().copied().max_by_key(|guild| {
+            let mut player = self.clone();
+            player.copied_guild = Some(*guild);
+            player.score(left, right).total()
+        })
+    }
+
+    pub fn copied_guild(&self) -> Option<Card> {
+        self.copied_guild
+    }
+
+    /// Copies the given neighbour's guild, which is then included in this player's score. Returns `true` if the guild
+    /// could be copied (see [`Player::guilds_to_copy`]), `false` otherwise.
+    pub fn copy_guild(&mut self, guild: &Card, left: &PublicPlayer, right: &PublicPlayer) -> bool {
+        if self.guilds_to_copy(left, right).contains(guild) {
+            self.copied_guild = Some(*guild);
             true
         } else {
             false
         }
     }
 
+    /// Returns `true` if this player has a power allowing them to play the final card of each age rather than discard
+    /// it.
+    pub fn can_play_seventh_card(&self) -> bool {
+        self.powers().any(|power| matches!(power, Power::PlaySeventhCard))
+    }
+
     /// Replaces this player's hand with the given cards, returning the hand the player had before the swap.
     pub fn swap_hand(&mut self, new_hand: Vec<Card>) -> Vec<Card> {
         mem::replace(&mut self.hand, new_hand)
     }
 
     /// Adds the given coins to this player's total.
-    fn add_coins(&mut self, coins: i32) {
+    pub fn add_coins(&mut self, coins: i32) {
         self.coins += coins;
     }
 
-    fn evaluate_green(colour_cards: &[Card]) -> f32 {
-        let mut science_items_count: HashMap<ScienceItem, i32> = HashMap::new();
+    /// Returns the coins paid out by the given power at the moment it is built, for example by the tavern or the
+    /// vineyard. Rewards that count game items count this player's items as they are now, and their neighbours' items
+    /// as they were at the start of the turn (from `visible_game`).
+    fn immediate_coins(&self, power: &Power, visible_game: &VisibleGame) -> i32 {
+        match power {
+            Power::Coins(coins) => *coins as i32,
+            Power::PerGameItemRewards(rewards) => {
+                let me = PublicPlayer::new(self);
+                rewards
+                    .iter()
+                    .map(|reward| {
+                        let count = reward.count(&me, visible_game.left_neighbour(), visible_game.right_neighbour());
+                        (count * reward.coins_per_thing) as i32
+                    })
+                    .sum()
+            }
+            _ => 0,
+        }
+    }
 
-        science_items_count.insert(ScienceItem::Compass, 0);
-        science_items_count.insert(ScienceItem::Cog, 0);
-        science_items_count.insert(ScienceItem::Tablet, 0);
+    /// Returns the powers of all of this player's built structures and built wonder stages.
+    fn powers(&self) -> impl Iterator<Item = &Power> {
+        self.built_structures
+            .iter()
+            .map(|card| card.power())
+            .chain(self.wonder_stage_powers())
+    }
 
-        for card in colour_cards.iter() {
-            if let Power::Science(science_items) = card.power() {
-                for science_item in science_items.iter() {
-                    let count = science_items_count.entry(*science_item).or_insert(0);
-                    *count += 1;
-                }
+    /// Returns the powers of this player's built wonder stages.
+    fn wonder_stage_powers(&self) -> impl Iterator<Item = &Power> {
+        self.wonder
+            .stages()
+            .iter()
+            .take(self.built_wonder_stages.len())
+            .flat_map(|stage| &stage.powers)
+    }
+
+    /// Returns the number of shields this player has, from both red cards and wonder stages.
+    pub fn shields(&self) -> u32 {
+        self.powers()
+            .map(|power| match power {
+                Power::Shields(shields) => *shields,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Resolves a military conflict against a neighbour with the given number of shields at the end of the given age.
+    /// The player gains a victory token if they have more shields than their neighbour, a defeat token if they have
+    /// fewer, and nothing if the number of shields is the same.
+    pub fn resolve_conflict(&mut self, neighbour_shields: u32, age: &Age) {
+        let shields = self.shields();
+        if shields > neighbour_shields {
+            self.military_tokens.push(age.victory_token_value());
+        } else if shields < neighbour_shields {
+            self.military_tokens.push(-1);
+        }
+    }
+
+    /// Returns the victory points provided by the given science symbols. Each entry in `science` is the symbols
+    /// provided by a single card or wonder stage. Where an entry has more than one symbol (for example, the scientists
+    /// guild), the player chooses one of them, and the choice that results in the most points is used.
+    fn evaluate_science(science: &[&Vec<ScienceItem>]) -> i32 {
+        // Points for a given number of compasses, cogs and tablets: the square of each count, plus 7 for each set of
+        // all three.
+        fn points(counts: &[i32; 3]) -> i32 {
+            counts.iter().map(|count| count * count).sum::<i32>() + 7 * counts.iter().min().unwrap()
+        }
+
+        // Tries every assignment of the remaining choices, returning the most points possible.
+        fn best(counts: &mut [i32; 3], choices: &[&&Vec<ScienceItem>]) -> i32 {
+            match choices.split_first() {
+                None => points(counts),
+                Some((choice, rest)) => choice
+                    .iter()
+                    .map(|science_item| {
+                        counts[*science_item as usize] += 1;
+                        let points = best(counts, rest);
+                        counts[*science_item as usize] -= 1;
+                        points
+                    })
+                    .max()
+                    .unwrap_or_else(|| best(counts, rest)),
             }
         }
 
-        let score_for_sets_of_identical_symbols: f32 = science_items_count
-            .iter()
-            .filter(|(_, count)| **count > 0)
-            .map(|(_, count)| (*count as f32).powf(2f32))
+        // Count the symbols we have no choice over up front, so we only need to iterate over the (few) choices.
+        let mut counts = [0; 3];
+        for science_items in science.iter().filter(|science_items| science_items.len() == 1) {
+            counts[science_items[0] as usize] += 1;
+        }
+        let choices: Vec<&&Vec<ScienceItem>> = science.iter().filter(|science_items| science_items.len() > 1).collect();
+
+        best(&mut counts, &choices)
+    }
+
+    /// Returns this player's end of game score, broken down into the categories of the official score pad. `left` and
+    /// `right` are the player's neighbours, which are needed to evaluate cards such as guilds.
+    pub fn score(&self, left: &PublicPlayer, right: &PublicPlayer) -> ScoreBreakdown {
+        let me = PublicPlayer::new(self);
+
+        // Sums the victory points of all built structures of the given colour, including any copied guild.
+        let points_for_colour = |colour: Colour| -> i32 {
+            self.built_structures
+                .iter()
+                .chain(self.copied_guild.iter())
+                .filter(|card| *card.colour() == colour)
+                .map(|card| match card.power() {
+                    Power::VictoryPoints(points) => *points as i32,
+                    Power::PerGameItemRewards(rewards) => rewards
+                        .iter()
+                        .map(|reward| (reward.count(&me, left, right) * reward.points_per_thing) as i32)
+                        .sum(),
+                    _ => 0,
+                })
+                .sum()
+        };
+
+        let wonders = self
+            .wonder_stage_powers()
+            .map(|power| match power {
+                Power::VictoryPoints(points) => *points as i32,
+                _ => 0,
+            })
             .sum();
 
-        let score_for_all_symbol_groups: f32 =
-            7f32 * *science_items_count.iter().min_by_key(|(_, count)| *count).unwrap().1 as f32;
+        let science: Vec<&Vec<ScienceItem>> = self
+            .powers()
+            .chain(self.copied_guild.iter().map(|card| card.power()))
+            .filter_map(|power| match power {
+                Power::Science(science_items) => Some(science_items),
+                _ => None,
+            })
+            .collect();
+
+        ScoreBreakdown {
+            military: self.military_tokens.iter().sum(),
+            treasury: self.coins / 3,
+            wonders,
+            civilian: points_for_colour(Colour::Blue),
+            commercial: points_for_colour(Colour::Yellow),
+            guilds: points_for_colour(Colour::Purple),
+            science: Self::evaluate_science(&science),
+        }
+    }
 
-        score_for_all_symbol_groups + score_for_sets_of_identical_symbols
+    /// Returns the cost of the next unbuilt stage of this player's wonder, or `None` if all stages have been built.
+    pub fn next_wonder_stage_cost(&self) -> Option<Cost> {
+        self.wonder.cost(self.built_wonder_stages.len() as u32).ok().cloned()
     }
 
-    fn evaluate_colour(cards_of_given_colour: &[Card]) -> f32 {
-        let colour = cards_of_given_colour.get(0).unwrap().colour();
+    pub fn can_play(&self, action: &Action, visible_game: &VisibleGame) -> bool {
+        self.check_action(action, visible_game).is_ok()
+    }
 
-        match colour {
-            Colour::Green => Self::evaluate_green(cards_of_given_colour),
-            _ => cards_of_given_colour.iter().map(|card| card.immediate_strength()).sum(),
+    /// Returns `Ok` if this player can perform the given action, or the reason they can't.
+    pub fn check_action(&self, action: &Action, visible_game: &VisibleGame) -> Result<(), IllegalActionReason> {
+        if !self.hand.iter().any(|c| c == action.card()) {
+            return Err(IllegalActionReason::CardNotInHand);
+        }
+        match action {
+            Action::Build(card, borrowing) => self.check_play_card(card, borrowing, visible_game),
+            Action::Wonder(_, borrowing) => self.check_build_wonder_stage(borrowing, visible_game),
+            Action::FreeBuild(card) if self.has_built(card) => Err(IllegalActionReason::AlreadyBuilt),
+            Action::FreeBuild(_) if !self.free_build_available() => Err(IllegalActionReason::NoFreeBuild),
+            Action::FreeBuild(_) | Action::Discard(_) => Ok(()),
         }
     }
 
-    fn strength_internal(cards: &[Card]) -> f32 {
-        let mut colour_to_structure = HashMap::new();
-        for structure in cards.iter() {
-            let colour_structures = colour_to_structure.entry(structure.colour()).or_insert_with(Vec::new);
-            colour_structures.push(*structure)
+    /// Checks the user can afford to play the given card from their hand, given the resources the player has access to.
+    fn check_play_card(
+        &self,
+        card: &Card,
+        borrowing: &Borrowing,
+        visible_game: &VisibleGame,
+    ) -> Result<(), IllegalActionReason> {
+        if self.has_built(card) {
+            return Err(IllegalActionReason::AlreadyBuilt);
+        }
+        // Cards chained from an already built structure are free, so there's never a need to borrow.
+        let affordable = if self.chained_from(card).is_some() {
+            !borrowing.has_borrowing()
+        } else {
+            self.can_afford(card.cost(), borrowing, visible_game)
+        };
+        if affordable {
+            Ok(())
+        } else {
+            Err(IllegalActionReason::CannotAfford)
         }
+    }
 
-        colour_to_structure
-            .iter()
-            .map(|colour_entry| Self::evaluate_colour(colour_entry.1))
-            .sum()
+    /// Returns the number of coins this player must pay to borrow one unit of the given resource from the given
+    /// neighbour. This is normally 2 coins, but is reduced to 1 coin by trading posts, the marketplace, and some wonder
+    /// stages.
+    fn borrowing_cost(&self, resource: &Resource, source: Source) -> i32 {
+        let discounted = self.powers().any(|power| match power {
+            Power::BuyBrownClockwise => resource.is_raw_material() && source == Source::LeftNeighbour,
+            Power::BuyBrownAntiClockwise => resource.is_raw_material() && source == Source::RightNeighbour,
+            Power::BuyGrey => !resource.is_raw_material(),
+            _ => false,
+        });
+        if discounted {
+            1
+        } else {
+            2
+        }
     }
 
-    /// Returns this player's "strength" -- a number where a higher value means the player is doing better than a lower
-    /// value.
-    pub fn strength(&self) -> f32 {
-        Self::strength_internal(&self.built_structures)
+    /// Returns the number of coins this player must pay to their left and right neighbours respectively for the given
+    /// borrowing.
+    pub fn borrowing_costs(&self, borrowing: &Borrowing) -> (i32, i32) {
+        let total = |borrows: &[Borrow], source: Source| -> i32 {
+            borrows
+                .iter()
+                .map(|borrow| self.borrowing_cost(&borrow.resource, source))
+                .sum()
+        };
+        (
+            total(&borrowing.left, Source::LeftNeighbour),
+            total(&borrowing.right, Source::RightNeighbour),
+        )
     }
 
-    pub fn can_play(&self, action: &Action, visible_game: &VisibleGame) -> bool {
-        match action {
-            Action::Build(card, borrowing) => self.can_play_card(card, borrowing, visible_game),
-            Action::Wonder(_, _) => todo!(),
-            Action::Discard(card) => self.hand.iter().any(|c| c == card),
+    /// Returns `true` if this player has already built the given structure. A player may not build two identical
+    /// structures, which includes cards with the same name from different ages (for example, the two looms).
+    pub fn has_built(&self, card: &Card) -> bool {
+        self.built_structures.iter().any(|built| built.name() == card.name())
+    }
+
+    /// Returns the built structure that chains to the given card, allowing the player to build it for free, or `None`
+    /// if there is no such structure.
+    pub fn chained_from(&self, card: &Card) -> Option<Card> {
+        self.built_structures
+            .iter()
+            .find(|built| built.chains_to().contains(card))
+            .copied()
+    }
+
+    /// Checks the user can afford to build the next stage of their wonder, given the resources they have access to.
+    fn check_build_wonder_stage(
+        &self,
+        borrowing: &Borrowing,
+        visible_game: &VisibleGame,
+    ) -> Result<(), IllegalActionReason> {
+        match self.next_wonder_stage_cost() {
+            Some(cost) if self.can_afford(&cost, borrowing, visible_game) => Ok(()),
+            Some(_) => Err(IllegalActionReason::CannotAfford),
+            None => Err(IllegalActionReason::WonderComplete),
         }
     }
 
-    /// Returns `true` if the user can afford to play the given card, given the resources the player
-    /// has access to.
-    fn can_play_card(&self, card: &Card, borrowing: &Borrowing, visible_game: &VisibleGame) -> bool {
+    /// Returns `true` if the user can afford the given cost using their own resources and the given borrowing.
+    fn can_afford(&self, cost: &Cost, borrowing: &Borrowing, visible_game: &VisibleGame) -> bool {
         /// Checks the given borrows against the given player, making sure the player has the right cards available.
         /// The resources provided by the borrows are subtracted from `cost`, and the coins needed for the borrows are
         /// added to `cost`.
-        fn check(borrows: &[Borrow], public_player: &PublicPlayer, cost: &mut Cost) -> bool {
+        fn check(
+            player: &Player,
+            borrows: &[Borrow],
+            source: Source,
+            public_player: &PublicPlayer,
+            cost: &mut Cost,
+        ) -> bool {
             let mut choices = vec![];
-            add_choices(
-                &public_player.built_structures,
-                &cost,
-                Source::LeftNeighbour, // Doesn't really matter as long as not Source::Own
-                &mut choices,
-            );
+            add_choices(&public_player.built_structures, cost, source, &mut choices);
             for borrow in borrows {
                 // Find and remove a card that matches. If we can't find one, the borrow is illegal.
                 let choice = choices
                     .iter()
-                    .position(|usable| usable.card == borrow.card && usable.resources.contains(&borrow.resource))
+                    .position(|usable| usable.card == Some(borrow.card) && usable.resources.contains(&borrow.resource))
                     .map(|index| choices.swap_remove(index));
                 match choice {
                     Some(_) => {
                         *cost -= &borrow.resource;
-                        cost.coins += 2; // TODO: cost of borrowing needs to vary depending on yellow cards.
+                        cost.coins += player.borrowing_cost(&borrow.resource, source);
                     }
                     None => return false,
                 }
@@ -211,20 +568,27 @@ impl Player {
             true
         }
 
-        // Can't play if the player doesn't have the card in hand.
-        if !self.hand.iter().any(|c| c == card) {
-            return false;
-        }
-
-        // Reduce the cost of the card by the player's own non choice resources, then check borrowing to left and right
-        // is legal and reduce the cost by the resources provided there too.
-        let mut cost = card.cost().clone();
+        // Reduce the cost by the player's own non choice resources, then check borrowing to left and right is legal and
+        // reduce the cost by the resources provided there too.
+        let mut cost = cost.clone();
         self.reduce_by_own_resources(&mut cost);
 
-        if !check(&borrowing.left, &visible_game.left_neighbour(), &mut cost) {
+        if !check(
+            self,
+            &borrowing.left,
+            Source::LeftNeighbour,
+            visible_game.left_neighbour(),
+            &mut cost,
+        ) {
             return false;
         }
-        if !(check(&borrowing.right, &visible_game.right_neighbour(), &mut cost)) {
+        if !(check(
+            self,
+            &borrowing.right,
+            Source::RightNeighbour,
+            visible_game.right_neighbour(),
+            &mut cost,
+        )) {
             return false;
         }
 
@@ -232,7 +596,7 @@ impl Player {
         // we're using too, but it doesn't yet (and may never because it's expensive to add this information). So
         // iterate over all possible combinations to see if one works.
         let mut choices = vec![];
-        add_choices(&self.built_structures, &cost, Source::Own, &mut choices);
+        self.add_own_choices(&cost, &mut choices);
 
         let mut combinations = 1;
         for choice in &choices {
@@ -264,23 +628,75 @@ impl Player {
     /// several different options when borrowing, each of these is returned as a separate action. This allows the player
     /// to choose how much money to spend on borrowing, and how much to give to each neighbour.
     ///
-    /// If the player cannot play the card, an empty vector is returned.
+    /// If the player has already built a structure that chains to the card, the card is free and a single action with
+    /// no borrowing is returned.
     ///
-    /// If `single_option` is `true`, only a single option will be returned, even if multiple are possible. The option
-    /// returned is selected at random from those available. This can be much more efficient if only a single option is
-    /// required as we can stop as soon as we find a valid option.
+    /// If the player cannot play the card, including because they have already built an identical structure, an empty
+    /// vector is returned.
+    ///
+    /// If `single_option` is given, only a single option will be returned, even if multiple are possible. The option
+    /// returned is selected at random from those available, using the given random number generator. This can be much
+    /// more efficient if only a single option is required
+    /// as we can stop as soon as we find a valid option.
     ///
     /// Note this function doesn't verify the cards the player has in their hand, meaning `card` can be a card the
     /// player doesn't have. As long as they can afford it, valid actions will be returned to achieve it.
-    pub fn options_for_card(&self, card: &Card, visible_game: &VisibleGame, single_option: bool) -> ActionOptions {
-        // Get the cost of the card, and subtract the Wonder starting resources and any non-choice resources owned by
-        // the player.
-        let mut cost = card.cost().clone();
+    pub fn options_for_card(
+        &self,
+        card: &Card,
+        visible_game: &VisibleGame,
+        single_option: Option<&mut ChaCha8Rng>,
+    ) -> ActionOptions {
+        if self.has_built(card) {
+            return ActionOptions { actions: vec![] };
+        }
+        if self.chained_from(card).is_some() {
+            return ActionOptions {
+                actions: vec![Action::Build(*card, Borrowing::no_borrowing())],
+            };
+        }
+        let mut options = self.options_for_cost(card.cost(), visible_game, single_option, |borrowing| {
+            Action::Build(*card, borrowing)
+        });
+        // Offer the free build last, and only if the card would otherwise cost something, so it isn't wasted.
+        if self.free_build_available() && !(options.own_cards_only() && card.cost().coins == 0) {
+            options.actions.push(Action::FreeBuild(*card));
+        }
+        options
+    }
+
+    /// As [`Player::options_for_card`], but returns the actions that use the given card to build the next stage of
+    /// the player's wonder. If all wonder stages have already been built, no actions are returned.
+    pub fn options_for_wonder_stage(
+        &self,
+        card: &Card,
+        visible_game: &VisibleGame,
+        single_option: Option<&mut ChaCha8Rng>,
+    ) -> ActionOptions {
+        match self.next_wonder_stage_cost() {
+            Some(cost) => self.options_for_cost(&cost, visible_game, single_option, |borrowing| {
+                Action::Wonder(*card, borrowing)
+            }),
+            None => ActionOptions { actions: vec![] },
+        }
+    }
+
+    /// Returns all possible ways of affording the given cost. `action` is used to turn each possible [`Borrowing`]
+    /// into an [`Action`]. See [`Player::options_for_card`] for details.
+    fn options_for_cost(
+        &self,
+        cost: &Cost,
+        visible_game: &VisibleGame,
+        single_option: Option<&mut ChaCha8Rng>,
+        action: impl Fn(Borrowing) -> Action,
+    ) -> ActionOptions {
+        // Subtract the Wonder starting resources and any non-choice resources owned by the player from the cost.
+        let mut cost = cost.clone();
         self.reduce_by_own_resources(&mut cost);
         if cost.satisfied() {
             // Can afford with own resources.
             return ActionOptions {
-                actions: vec![Action::Build(*card, Borrowing::no_borrowing())],
+                actions: vec![action(Borrowing::no_borrowing())],
             };
         }
 
@@ -288,7 +704,7 @@ impl Player {
         // iterate over all possible combinations of those cards. We filter our entire cards that don't have the
         // resources we need, and filter choice cards to just the resources required.
         let mut choices = vec![];
-        add_choices(&self.built_structures, &cost, Source::Own, &mut choices);
+        self.add_own_choices(&cost, &mut choices);
         let own_choices_count = choices.len();
         add_choices(
             &visible_game.left_neighbour().built_structures,
@@ -305,10 +721,14 @@ impl Player {
 
         // If returning a single option, shuffle the choices so we select the option returned at random. Own choices
         // must always come before neighbour choices, though, so we don't over-borrow.
-        if single_option {
-            choices[..own_choices_count].shuffle(&mut thread_rng());
-            choices[own_choices_count..].shuffle(&mut thread_rng());
-        }
+        let single_option = match single_option {
+            Some(rng) => {
+                choices[..own_choices_count].shuffle(rng);
+                choices[own_choices_count..].shuffle(rng);
+                true
+            }
+            None => false,
+        };
 
         let mut actions = vec![];
         if !choices.is_empty() {
@@ -338,19 +758,21 @@ impl Player {
                     if choice.source == Source::Own {
                         cost_copy -= &choice.resources[index];
                     } else if index > 0 {
-                        // TODO: cost of borrowing needs to vary depending on yellow cards.
-                        if cost_copy.coins <= -2 {
+                        // Neighbours' resources always come from a card, as wonder stages can't be borrowed from.
+                        let card = choice.card.unwrap();
+                        let price = self.borrowing_cost(&choice.resources[index - 1], choice.source);
+                        if cost_copy.coins <= -price {
                             if !cost_copy.has(&choice.resources[index - 1]) {
                                 // We already have enough of whatever this option provides. Therefore, this particular
                                 // combination is not valid. Skip to the next.
                                 continue 'outer;
                             }
                             cost_copy -= &choice.resources[index - 1];
-                            cost_copy.coins += 2;
+                            cost_copy.coins += price;
                             if choice.source == Source::LeftNeighbour {
-                                left_borrowing.push(Borrow::new(choice.card, choice.resources[index - 1]));
+                                left_borrowing.push(Borrow::new(card, choice.resources[index - 1]));
                             } else {
-                                right_borrowing.push(Borrow::new(choice.card, choice.resources[index - 1]));
+                                right_borrowing.push(Borrow::new(card, choice.resources[index - 1]));
                             }
                         } else {
                             // Out of money for borrowing.
@@ -360,10 +782,7 @@ impl Player {
                     c /= len as u32;
                 }
                 if cost_copy.satisfied() {
-                    actions.push(Action::Build(
-                        *card,
-                        Borrowing::new(left_borrowing.clone(), right_borrowing.clone()),
-                    ));
+                    actions.push(action(Borrowing::new(left_borrowing.clone(), right_borrowing.clone())));
                     if single_option {
                         break 'outer;
                     }
@@ -374,13 +793,13 @@ impl Player {
         ActionOptions { actions }
     }
 
-    /// Reduces `cost` by the resources provided by this player's built structures, their coins, and their wonder's
-    /// starting resource. "Choice" resources are not used.
+    /// Reduces `cost` by the resources provided by this player's built structures and wonder stages, their coins, and
+    /// their wonder's starting resource. "Choice" resources are not used.
     fn reduce_by_own_resources(&self, cost: &mut Cost) {
         *cost -= &self.wonder.starting_resource();
         cost.coins -= self.coins;
-        for card in &self.built_structures {
-            if let Power::Producer(produced_resources) | Power::PurchasableProducer(produced_resources) = card.power() {
+        for power in self.powers() {
+            if let Power::Producer(produced_resources) | Power::PurchasableProducer(produced_resources) = power {
                 match produced_resources {
                     ProducedResources::Single(resource) => *cost -= resource,
                     ProducedResources::Double(resource) => {
@@ -392,6 +811,15 @@ impl Player {
             }
         }
     }
+
+    /// Adds to `choices` the "choice" resources provided by this player's built structures and built wonder stages.
+    /// See [`add_choices`].
+    fn add_own_choices(&self, cost: &Cost, choices: &mut Vec<UsableResources>) {
+        add_choices(&self.built_structures, cost, Source::Own, choices);
+        for power in self.wonder_stage_powers() {
+            add_power_choices(None, power, cost, Source::Own, choices);
+        }
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -402,7 +830,9 @@ enum Source {
 }
 
 struct UsableResources {
-    card: Card,
+    /// The card providing the resources, or `None` if they're provided by a wonder stage. Wonder stages are only ever
+    /// used by their owner, never borrowed by neighbours.
+    card: Option<Card>,
     resources: Vec<Resource>,
     source: Source,
 }
@@ -412,50 +842,60 @@ struct UsableResources {
 /// entirely. Cards that provide options of resources are reduced to only those resources we require.
 fn add_choices(cards: &[Card], cost: &Cost, source: Source, choices: &mut Vec<UsableResources>) {
     for card in cards {
-        match (card.power(), source) {
-            // Make sure we only borrow brown and grey cards from neighbours (not yellow).
-            (Power::Producer(produced_resources), Source::Own)
-            | (Power::PurchasableProducer(produced_resources), _) => {
-                match produced_resources {
-                    ProducedResources::Single(resource) => {
-                        // Filter out single choice own cards as we'll have already dealt with these. Only
-                        // include the card if it has a resource we need.
-                        if source != Source::Own && cost.has(resource) {
+        add_power_choices(Some(*card), card.power(), cost, source, choices);
+    }
+}
+
+/// As [`add_choices`], but for a single power, provided by the given card (or by a wonder stage if `card` is `None`).
+fn add_power_choices(
+    card: Option<Card>,
+    power: &Power,
+    cost: &Cost,
+    source: Source,
+    choices: &mut Vec<UsableResources>,
+) {
+    match (power, source) {
+        // Make sure we only borrow brown and grey cards from neighbours (not yellow).
+        (Power::Producer(produced_resources), Source::Own) | (Power::PurchasableProducer(produced_resources), _) => {
+            match produced_resources {
+                ProducedResources::Single(resource) => {
+                    // Filter out single choice own cards as we'll have already dealt with these. Only
+                    // include the card if it has a resource we need.
+                    if source != Source::Own && cost.has(resource) {
+                        choices.push(UsableResources {
+                            card,
+                            resources: vec![*resource],
+                            source,
+                        });
+                    }
+                }
+                ProducedResources::Double(resource) => {
+                    // Filter out single choice own cards as we'll have already dealt with these. Add two
+                    // copies of the card so we can choose to use one resource or both.
+                    if source != Source::Own && cost.has(resource) {
+                        for _ in 0..2 {
                             choices.push(UsableResources {
-                                card: *card,
+                                card,
                                 resources: vec![*resource],
                                 source,
                             });
                         }
                     }
-                    ProducedResources::Double(resource) => {
-                        // Filter out single choice own cards as we'll have already dealt with these. Add two
-                        // copies of the card so we can choose to use one resource or both.
-                        if source != Source::Own && cost.has(resource) {
-                            for _ in 0..2 {
-                                choices.push(UsableResources {
-                                    card: *card,
-                                    resources: vec![*resource],
-                                    source,
-                                });
-                            }
-                        }
-                    }
-                    ProducedResources::Choice(resources) => {
-                        // Filter the choices to only those we need.
-                        let resources: Vec<Resource> = resources.iter().filter(|r| cost.has(r)).cloned().collect();
-                        if !resources.is_empty() {
-                            choices.push(UsableResources {
-                                card: *card,
-                                resources,
-                                source,
-                            });
-                        }
+                }
+                ProducedResources::Choice(resources) => {
+                    // Filter the choices to only those we need.
+                    let resources: Vec<Resource> = resources.iter().filter(|r| cost.has(r)).cloned().collect();
+                    if !resources.is_empty() {
+                        choices.push(UsableResources {
+                            card,
+                            resources,
+                            source,
+                        });
                     }
                 }
             }
-            _ => {}
         }
+        _ => {}
     }
 }
 
@@ -465,7 +905,15 @@ fn add_choices(cards: &[Card], cost: &Cost, source: Source, choices: &mut Vec<Us
 pub struct PublicPlayer {
     pub wonder: WonderBoard,
     pub built_structures: Vec<Card>,
+    /// The number of wonder stages built. The cards used to build them are face down and therefore not public.
+    pub wonder_stages_built: u32,
+    pub military_tokens: Vec<i32>,
     pub coins: i32,
+    /// Whether the player has used their free build this age (see [`Power::FreeBuildOncePerAge`]). Public because the
+    /// free build is played face up.
+    pub free_build_used: bool,
+    /// The neighbour's guild this player copies during final scoring (see [`Power::CopyGuild`]), if any.
+    pub copied_guild: Option<Card>,
 }
 
 impl PublicPlayer {
@@ -475,9 +923,28 @@ impl PublicPlayer {
         PublicPlayer {
             wonder: player.wonder,
             built_structures: player.built_structures.clone(),
+            wonder_stages_built: player.built_wonder_stages.len() as u32,
+            military_tokens: player.military_tokens.clone(),
             coins: player.coins,
+            free_build_used: player.free_build_used,
+            copied_guild: player.copied_guild,
         }
     }
+
+    /// Returns all the things this player has that can be counted by a [`crate::power::PerGameItemReward`].
+    pub fn countable_game_items(&self) -> Vec<CountableGameItem> {
+        self.built_structures
+            .iter()
+            .map(|card| CountableGameItem::CountableCard(*card))
+            .chain(
+                self.military_tokens
+                    .iter()
+                    .filter(|token| **token == -1)
+                    .map(|_| CountableGameItem::DefeatToken),
+            )
+            .chain((0..self.wonder_stages_built).map(|_| CountableGameItem::CompletedWonderStage))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -485,6 +952,7 @@ mod tests {
     use Card::*;
 
     use super::*;
+    use crate::utils::derive_rng;
 
     #[test]
     fn options_for_card_returns_nothing_if_insufficient_resources() {
@@ -493,7 +961,7 @@ mod tests {
         assert_eq!(
             0,
             player
-                .options_for_card(&Stockade, &visible_game(&players()), false)
+                .options_for_card(&Stockade, &visible_game(&players()), None)
                 .actions
                 .len()
         );
@@ -507,7 +975,7 @@ mod tests {
         assert_eq!(
             0,
             player
-                .options_for_card(&TreeFarm, &visible_game(&players()), false)
+                .options_for_card(&TreeFarm, &visible_game(&players()), None)
                 .actions
                 .len()
         );
@@ -520,7 +988,7 @@ mod tests {
         assert_eq!(
             1,
             player
-                .options_for_card(&Barracks, &visible_game(&players()), false)
+                .options_for_card(&Barracks, &visible_game(&players()), None)
                 .actions
                 .len()
         );
@@ -533,7 +1001,7 @@ mod tests {
         assert_eq!(
             1,
             player
-                .options_for_card(&TreeFarm, &visible_game(&players()), false)
+                .options_for_card(&TreeFarm, &visible_game(&players()), None)
                 .actions
                 .len()
         );
@@ -546,7 +1014,7 @@ mod tests {
         assert_eq!(
             1,
             player
-                .options_for_card(&LumberYard, &visible_game(&players()), false)
+                .options_for_card(&LumberYard, &visible_game(&players()), None)
                 .actions
                 .len()
         );
@@ -560,7 +1028,7 @@ mod tests {
         assert_eq!(
             1,
             player
-                .options_for_card(&Stockade, &visible_game(&players()), false)
+                .options_for_card(&Stockade, &visible_game(&players()), None)
                 .actions
                 .len()
         );
@@ -575,7 +1043,7 @@ mod tests {
         assert_eq!(
             0,
             player
-                .options_for_card(&Temple, &visible_game(&players()), false)
+                .options_for_card(&Temple, &visible_game(&players()), None)
                 .actions
                 .len()
         );
@@ -590,7 +1058,7 @@ mod tests {
         assert_eq!(
             1,
             player
-                .options_for_card(&Stockade, &visible_game(&players()), false)
+                .options_for_card(&Stockade, &visible_game(&players()), None)
                 .actions
                 .len()
         );
@@ -605,7 +1073,7 @@ mod tests {
         assert_eq!(
             1,
             player
-                .options_for_card(&Caravansery, &visible_game(&players()), false)
+                .options_for_card(&Caravansery, &visible_game(&players()), None)
                 .actions
                 .len()
         );
@@ -619,7 +1087,7 @@ mod tests {
         assert_eq!(
             1,
             player
-                .options_for_card(&Stockade, &visible_game(&public_players), false)
+                .options_for_card(&Stockade, &visible_game(&public_players), None)
                 .actions
                 .len()
         );
@@ -634,7 +1102,7 @@ mod tests {
         assert_eq!(
             0,
             player
-                .options_for_card(&Stockade, &visible_game(&public_players), false)
+                .options_for_card(&Stockade, &visible_game(&public_players), None)
                 .actions
                 .len()
         );
@@ -649,7 +1117,7 @@ mod tests {
         assert_eq!(
             0,
             player
-                .options_for_card(&Caravansery, &visible_game(&public_players), false)
+                .options_for_card(&Caravansery, &visible_game(&public_players), None)
                 .actions
                 .len()
         );
@@ -664,7 +1132,7 @@ mod tests {
         assert_eq!(
             0,
             player
-                .options_for_card(&Caravansery, &visible_game(&public_players), false)
+                .options_for_card(&Caravansery, &visible_game(&public_players), None)
                 .actions
                 .len()
         );
@@ -678,7 +1146,7 @@ mod tests {
         assert_eq!(
             2,
             player
-                .options_for_card(&Stockade, &visible_game(&public_players), false)
+                .options_for_card(&Stockade, &visible_game(&public_players), None)
                 .actions
                 .len()
         );
@@ -693,7 +1161,7 @@ mod tests {
         assert_eq!(
             1,
             player
-                .options_for_card(&Stockade, &visible_game(&public_players), true)
+                .options_for_card(&Stockade, &visible_game(&public_players), Some(&mut derive_rng(0, 0)))
                 .actions
                 .len()
         );
@@ -708,7 +1176,7 @@ mod tests {
         assert_eq!(
             1,
             player
-                .options_for_card(&Caravansery, &visible_game(&public_players), false)
+                .options_for_card(&Caravansery, &visible_game(&public_players), None)
                 .actions
                 .len()
         );
@@ -724,7 +1192,7 @@ mod tests {
         assert_eq!(
             1,
             player
-                .options_for_card(&Stockade, &visible_game(&public_players), false)
+                .options_for_card(&Stockade, &visible_game(&public_players), None)
                 .actions
                 .len()
         );
@@ -740,7 +1208,7 @@ mod tests {
         assert_eq!(
             1,
             player
-                .options_for_card(&Stockade, &visible_game(&public_players), false)
+                .options_for_card(&Stockade, &visible_game(&public_players), None)
                 .actions
                 .len()
         );
@@ -760,7 +1228,7 @@ mod tests {
         assert_eq!(
             6,
             player
-                .options_for_card(&Laboratory, &visible_game(&public_players), false)
+                .options_for_card(&Laboratory, &visible_game(&public_players), None)
                 .actions
                 .len()
         );
@@ -779,7 +1247,7 @@ mod tests {
         assert_eq!(
             1,
             player
-                .options_for_card(&Aqueduct, &visible_game(&public_players), false)
+                .options_for_card(&Aqueduct, &visible_game(&public_players), None)
                 .actions
                 .len()
         );
@@ -793,7 +1261,7 @@ mod tests {
         assert_eq!(
             0,
             player
-                .options_for_card(&Stockade, &visible_game(&public_players), false)
+                .options_for_card(&Stockade, &visible_game(&public_players), None)
                 .actions
                 .len()
         );
@@ -808,12 +1276,193 @@ mod tests {
         assert_eq!(
             1,
             player
-                .options_for_card(&Baths, &visible_game(&players()), false)
+                .options_for_card(&Baths, &visible_game(&players()), None)
                 .actions
                 .len()
         );
     }
 
+    #[test]
+    fn options_for_card_returns_free_option_if_chained() {
+        // Aqueduct requires 3 stone, which we don't have, but we've built the baths which chain to it.
+        let mut player = new_player(vec![]);
+        player.built_structures.push(Baths);
+        let options = player.options_for_card(&Aqueduct, &visible_game(&players()), None);
+        assert_eq!(1, options.actions.len());
+        assert_eq!(true, options.own_cards_only());
+    }
+
+    #[test]
+    fn can_play_returns_true_if_chained() {
+        let mut player = new_player(vec![Aqueduct]);
+        player.built_structures.push(Baths);
+        assert_eq!(
+            true,
+            player.can_play(
+                &Action::Build(Aqueduct, Borrowing::no_borrowing()),
+                &visible_game(&players())
+            )
+        );
+    }
+
+    #[test]
+    fn can_play_returns_false_if_borrowing_for_chained_card() {
+        let mut player = new_player(vec![Aqueduct]);
+        player.built_structures.push(Baths);
+        let public_players = players_with_resources(vec![StonePit], vec![]);
+        assert_eq!(
+            false,
+            player.can_play(
+                &Action::Build(
+                    Aqueduct,
+                    Borrowing::new(vec![Borrow::new(StonePit, Resource::Stone)], vec![])
+                ),
+                &visible_game(&public_players)
+            )
+        );
+    }
+
+    #[test]
+    fn chained_from_returns_chaining_structure() {
+        let mut player = new_player(vec![]);
+        player.built_structures.push(Scriptorium);
+        assert_eq!(Some(Scriptorium), player.chained_from(&Library));
+        assert_eq!(Some(Scriptorium), player.chained_from(&Courthouse));
+        assert_eq!(None, player.chained_from(&Aqueduct));
+    }
+
+    #[test]
+    fn options_for_card_borrows_for_one_coin_with_trading_post() {
+        // Stockade requires 1 wood. We only have 1 coin, but the west trading post lets us buy brown resources from our
+        // clockwise (ie. left) neighbour for 1 coin.
+        let mut player = new_player(vec![]);
+        player.coins = 1;
+        player.built_structures.push(WestTradingPost);
+        let public_players = players_with_resources(vec![LumberYard], vec![LumberYard]);
+        let options = player.options_for_card(&Stockade, &visible_game(&public_players), None);
+        assert_eq!(1, options.actions.len());
+        assert!(matches!(&options.actions[0], Action::Build(_, borrowing) if borrowing.left.len() == 1));
+    }
+
+    #[test]
+    fn borrowing_costs_depend_on_yellow_cards() {
+        let borrowing = Borrowing::new(
+            vec![
+                Borrow::new(LumberYard, Resource::Wood),
+                Borrow::new(Loom1, Resource::Loom),
+            ],
+            vec![
+                Borrow::new(ClayPool, Resource::Clay),
+                Borrow::new(Press1, Resource::Papyrus),
+            ],
+        );
+        let mut player = new_player(vec![]);
+        assert_eq!((4, 4), player.borrowing_costs(&borrowing));
+        player.built_structures.push(EastTradingPost);
+        assert_eq!((4, 3), player.borrowing_costs(&borrowing));
+        player.built_structures.push(Marketplace);
+        assert_eq!((3, 2), player.borrowing_costs(&borrowing));
+    }
+
+    #[test]
+    fn borrowing_costs_include_wonder_stage_discounts() {
+        // The first stage of the Statue of Zeus (side B) lets the player buy brown resources from both neighbours for 1
+        // coin.
+        let borrowing = Borrowing::new(
+            vec![Borrow::new(LumberYard, Resource::Wood)],
+            vec![Borrow::new(Glassworks1, Resource::Glass)],
+        );
+        let mut player = Player::new(WonderType::StatueOfZeus, WonderSide::B);
+        player.built_wonder_stages.push(Some(Altar));
+        assert_eq!((1, 2), player.borrowing_costs(&borrowing));
+    }
+
+    #[test]
+    fn do_action_pays_discounted_price_to_neighbour() {
+        let mut player = new_player(vec![Stockade]);
+        player.built_structures.push(WestTradingPost);
+        let mut coin_transfers = vec![];
+        let public_players = players_with_resources(vec![LumberYard], vec![]);
+        assert_eq!(
+            Ok(()),
+            player.do_action(
+                &Action::Build(
+                    Stockade,
+                    Borrowing::new(vec![Borrow::new(LumberYard, Resource::Wood)], vec![])
+                ),
+                &visible_game(&public_players),
+                &mut vec![],
+                &mut coin_transfers,
+            )
+        );
+        assert_eq!(2, player.coins);
+        assert_eq!(
+            vec![CoinTransfer {
+                from: 1,
+                to: 2,
+                coins: 1
+            }],
+            coin_transfers
+        );
+    }
+
+    #[test]
+    fn options_for_card_returns_nothing_if_already_built() {
+        let mut player = new_player(vec![]);
+        player.built_structures.push(LumberYard);
+        assert_eq!(
+            0,
+            player
+                .options_for_card(&LumberYard, &visible_game(&players()), None)
+                .actions
+                .len()
+        );
+    }
+
+    #[test]
+    fn can_play_returns_false_if_already_built() {
+        let mut player = new_player(vec![LumberYard]);
+        player.built_structures.push(LumberYard);
+        assert_eq!(
+            false,
+            player.can_play(
+                &Action::Build(LumberYard, Borrowing::no_borrowing()),
+                &visible_game(&players())
+            )
+        );
+    }
+
+    #[test]
+    fn can_play_returns_false_if_structure_with_same_name_already_built() {
+        let mut player = new_player(vec![Loom2]);
+        player.built_structures.push(Loom1);
+        assert_eq!(true, player.has_built(&Loom2));
+        assert_eq!(
+            false,
+            player.can_play(
+                &Action::Build(Loom2, Borrowing::no_borrowing()),
+                &visible_game(&players())
+            )
+        );
+    }
+
+    #[test]
+    fn can_play_allows_wonder_stage_and_discard_of_already_built_card() {
+        let mut player = new_player(vec![LumberYard]);
+        player.built_structures = vec![LumberYard, Sawmill];
+        assert_eq!(
+            true,
+            player.can_play(
+                &Action::Wonder(LumberYard, Borrowing::no_borrowing()),
+                &visible_game(&players())
+            )
+        );
+        assert_eq!(
+            true,
+            player.can_play(&Action::Discard(LumberYard), &visible_game(&players()))
+        );
+    }
+
     #[test]
     fn can_play_returns_false_if_player_does_not_have_card() {
         let player = new_player(vec![LumberYard]);
@@ -847,6 +1496,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_play_uses_choice_resources_from_wonder_stages() {
+        let player = lighthouse_b_player(vec![Baths]);
+        assert_eq!(
+            true,
+            player.can_play(
+                &Action::Build(Baths, Borrowing::no_borrowing()),
+                &visible_game(&players())
+            )
+        );
+    }
+
+    #[test]
+    fn can_play_uses_each_wonder_stage_choice_resource_once() {
+        // Aqueduct requires 3 stone, but the wonder stage only provides one.
+        let player = lighthouse_b_player(vec![Aqueduct]);
+        assert_eq!(
+            false,
+            player.can_play(
+                &Action::Build(Aqueduct, Borrowing::no_borrowing()),
+                &visible_game(&players())
+            )
+        );
+    }
+
+    #[test]
+    fn options_for_card_uses_choice_resources_from_wonder_stages() {
+        let player = lighthouse_b_player(vec![Baths]);
+        let options = player.options_for_card(&Baths, &visible_game(&players()), None);
+        assert_eq!(true, options.own_cards_only());
+    }
+
+    #[test]
+    fn options_for_card_combines_wonder_stage_choice_with_borrowing() {
+        // Temple requires wood, clay and glass. The Lighthouse provides glass and either wood or clay, so we need to
+        // borrow one of wood (from the left) or clay (from the right).
+        let player = lighthouse_b_player(vec![Temple]);
+        let public_players = players_with_resources(vec![LumberYard], vec![ClayPool]);
+        let options = player.options_for_card(&Temple, &visible_game(&public_players), None);
+        assert_eq!(2, options.actions.len());
+        assert_eq!(
+            Action::Build(
+                Temple,
+                Borrowing::new(vec![Borrow::new(LumberYard, Resource::Wood)], vec![])
+            ),
+            options.actions[0]
+        );
+        assert_eq!(
+            Action::Build(
+                Temple,
+                Borrowing::new(vec![], vec![Borrow::new(ClayPool, Resource::Clay)])
+            ),
+            options.actions[1]
+        );
+    }
+
     #[test]
     fn can_play_returns_false_if_borrowing_required_but_not_specified() {
         // Stockade requires 1 wood, we *can* borrow from a neighbour, but our action says we're not doing any borrowing.
@@ -941,27 +1646,167 @@ mod tests {
     }
 
     #[test]
-    fn strength_returns_sum_of_card_strengths() {
-        assert_eq!(0.0, Player::strength_internal(&[StonePit]));
-        assert_eq!(5.0, Player::strength_internal(&[StonePit, Quarry, Aqueduct]));
+    fn score_counts_civilian_points() {
+        assert_eq!(0, score_for_structures(vec![StonePit]).civilian);
+        assert_eq!(5, score_for_structures(vec![StonePit, Quarry, Aqueduct]).civilian);
         assert_eq!(
-            6.0,
-            Player::strength_internal(&[StonePit, Quarry, Aqueduct, Loom1, Apothecary])
+            5,
+            score_for_structures(vec![StonePit, Quarry, Aqueduct, Loom1, Apothecary]).civilian
         );
     }
 
     #[test]
-    fn strength_returns_correct_strength_of_green_structures() {
-        assert_eq!(1.0, Player::strength_internal(&[Lodge]));
-        assert_eq!(4.0, Player::strength_internal(&[Lodge, Apothecary]));
-        assert_eq!(9.0, Player::strength_internal(&[Lodge, Apothecary, Dispensary]));
-        assert_eq!(10.0, Player::strength_internal(&[Lodge, Workshop, Library]));
+    fn score_counts_science_points() {
+        assert_eq!(1, score_for_structures(vec![Lodge]).science);
+        assert_eq!(4, score_for_structures(vec![Lodge, Apothecary]).science);
+        assert_eq!(9, score_for_structures(vec![Lodge, Apothecary, Dispensary]).science);
+        assert_eq!(10, score_for_structures(vec![Lodge, Workshop, Library]).science);
         assert_eq!(
-            21.0,
-            Player::strength_internal(&[Lodge, Apothecary, Dispensary, Laboratory, Workshop, Library])
+            21,
+            score_for_structures(vec![Lodge, Apothecary, Dispensary, Laboratory, Workshop, Library]).science
         ); // rulebook example
     }
 
+    #[test]
+    fn score_chooses_best_science_symbol_for_choices() {
+        // The scientists guild counts as just one symbol.
+        assert_eq!(1, score_for_structures(vec![ScientistsGuild]).science);
+        // We already have a set, so any choice makes a pair (4 + 1 + 1 + 7).
+        assert_eq!(
+            13,
+            score_for_structures(vec![Apothecary, Workshop, Scriptorium, ScientistsGuild]).science
+        );
+        // Best to complete a set (4 + 1 + 1 + 7) rather than make a third compass (9 + 1).
+        assert_eq!(
+            13,
+            score_for_structures(vec![Apothecary, Lodge, Workshop, ScientistsGuild]).science
+        );
+        // Best to make a fourth compass (16) rather than a cog (9 + 1).
+        assert_eq!(
+            16,
+            score_for_structures(vec![Apothecary, Lodge, Dispensary, ScientistsGuild]).science
+        );
+    }
+
+    #[test]
+    fn score_chooses_best_science_symbols_across_wonder_stages_and_guilds() {
+        // The second stage of the Hanging Gardens of Babylon (side A) provides a science symbol of choice.
+        let mut player = Player::new(WonderType::HangingGardensOfBabylon, WonderSide::A);
+        player.coins = 0;
+        player.built_structures = vec![Apothecary, ScientistsGuild];
+        player.built_wonder_stages = vec![Some(Altar), Some(Baths)];
+        let public_players = players();
+        assert_eq!(10, player.score(&public_players[2], &public_players[0]).science);
+    }
+
+    #[test]
+    fn score_counts_treasury_and_wonder_points() {
+        // The first and third stages of the Colossus of Rhodes (side A) provide 3 and 7 points.
+        let mut player = new_player(vec![]);
+        player.coins = 8;
+        player.built_wonder_stages = vec![Some(Altar), Some(Baths), Some(Theater)];
+        let public_players = players();
+        let score = player.score(&public_players[2], &public_players[0]);
+        assert_eq!(2, score.treasury);
+        assert_eq!(10, score.wonders);
+    }
+
+    #[test]
+    fn score_counts_commercial_and_guild_points() {
+        // Haven provides 1 point per own brown card. Workers guild provides 1 point per neighbours' brown card.
+        let mut player = new_player(vec![]);
+        player.built_structures = vec![Haven, WorkersGuild, StonePit];
+        let public_players = players_with_resources(vec![LumberYard, OreVein], vec![Quarry]);
+        let score = player.score(&public_players[2], &public_players[0]);
+        assert_eq!(1, score.commercial);
+        assert_eq!(3, score.guilds);
+    }
+
+    #[test]
+    fn score_includes_military_tokens_and_strategists_guild() {
+        let mut player = new_player(vec![]);
+        player.built_structures.push(StrategistsGuild);
+        player.military_tokens = vec![5];
+        let mut public_players = players();
+        public_players[0].military_tokens = vec![-1, -1];
+        public_players[2].military_tokens = vec![-1, 3];
+        let score = player.score(&public_players[2], &public_players[0]);
+        assert_eq!(5, score.military);
+        assert_eq!(3, score.guilds);
+    }
+
+    #[test]
+    fn guilds_to_copy_returns_neighbours_guilds() {
+        let player = olympia_b_player();
+        let public_players = players_with_resources(vec![WorkersGuild, LumberYard], vec![ScientistsGuild]);
+        assert_eq!(
+            vec![WorkersGuild, ScientistsGuild],
+            player.guilds_to_copy(&public_players[2], &public_players[0])
+        );
+    }
+
+    #[test]
+    fn guilds_to_copy_is_empty_without_power() {
+        let player = new_player(vec![]);
+        let public_players = players_with_resources(vec![WorkersGuild], vec![ScientistsGuild]);
+        assert!(player.guilds_to_copy(&public_players[2], &public_players[0]).is_empty());
+    }
+
+    #[test]
+    fn guilds_to_copy_excludes_guilds_already_built() {
+        let mut player = olympia_b_player();
+        player.built_structures.push(WorkersGuild);
+        let public_players = players_with_resources(vec![WorkersGuild], vec![]);
+        assert!(player.guilds_to_copy(&public_players[2], &public_players[0]).is_empty());
+    }
+
+    #[test]
+    fn best_guild_to_copy_picks_highest_scoring_guild() {
+        // Workers guild gives 2 points for the neighbours' brown cards; scientists guild gives 1 point for a single
+        // science symbol.
+        let player = olympia_b_player();
+        let public_players = players_with_resources(vec![WorkersGuild, LumberYard, OreVein], vec![ScientistsGuild]);
+        assert_eq!(
+            Some(WorkersGuild),
+            player.best_guild_to_copy(&[WorkersGuild, ScientistsGuild], &public_players[2], &public_players[0])
+        );
+    }
+
+    #[test]
+    fn best_guild_to_copy_only_picks_from_given_guilds() {
+        let player = olympia_b_player();
+        let public_players = players_with_resources(vec![WorkersGuild, LumberYard, OreVein], vec![ScientistsGuild]);
+        assert_eq!(
+            Some(ScientistsGuild),
+            player.best_guild_to_copy(&[ScientistsGuild], &public_players[2], &public_players[0])
+        );
+    }
+
+    #[test]
+    fn score_includes_copied_guild() {
+        let mut player = olympia_b_player();
+        let public_players = players_with_resources(vec![WorkersGuild, LumberYard, OreVein], vec![ScientistsGuild]);
+        assert_eq!(
+            true,
+            player.copy_guild(&ScientistsGuild, &public_players[2], &public_players[0])
+        );
+        assert_eq!(Some(ScientistsGuild), player.copied_guild());
+        let score = player.score(&public_players[2], &public_players[0]);
+        assert_eq!(0, score.guilds);
+        assert_eq!(1, score.science);
+    }
+
+    #[test]
+    fn copy_guild_returns_false_for_guild_not_built_by_neighbour() {
+        let mut player = olympia_b_player();
+        let public_players = players_with_resources(vec![WorkersGuild], vec![]);
+        assert_eq!(
+            false,
+            player.copy_guild(&ScientistsGuild, &public_players[2], &public_players[0])
+        );
+        assert_eq!(None, player.copied_guild());
+    }
+
     #[test]
     fn do_action_returns_false_if_action_not_playable() {
         let mut player = new_player(vec![LumberYard]);
@@ -992,13 +1837,12 @@ mod tests {
         let mut discard_pile = vec![];
         assert_eq!(1, player.hand.len());
         assert_eq!(
-            true,
+            Ok(()),
             player.do_action(
                 &Action::Discard(LumberYard),
                 &visible_game(&players()),
-                &mut new_player(vec![]),
-                &mut new_player(vec![]),
-                &mut discard_pile
+                &mut discard_pile,
+                &mut vec![]
             )
         );
         assert_eq!(1, discard_pile.len());
@@ -1010,25 +1854,324 @@ mod tests {
         let mut player = new_player(vec![LumberYard]);
         assert_eq!(3, player.coins);
         assert_eq!(
-            true,
+            Ok(()),
             player.do_action(
                 &Action::Discard(LumberYard),
                 &visible_game(&players()),
-                &mut new_player(vec![]),
-                &mut new_player(vec![]),
+                &mut vec![],
                 &mut vec![]
             )
         );
         assert_eq!(6, player.coins);
     }
 
+    #[test]
+    fn options_for_wonder_stage_returns_one_option_if_sufficient_resources() {
+        // The first stage of the Colossus of Rhodes (side A) requires 2 wood.
+        let mut player = new_player(vec![Sawmill, Stockade]);
+        player.coins = 4;
+        build(&mut player, Sawmill);
+        let options = player.options_for_wonder_stage(&Stockade, &visible_game(&players()), None);
+        assert_eq!(1, options.actions.len());
+        assert_eq!(true, options.own_cards_only());
+    }
+
+    #[test]
+    fn options_for_wonder_stage_borrows_from_neighbours() {
+        // The first stage of the Colossus of Rhodes (side A) requires 2 wood, one from each neighbour.
+        let mut player = new_player(vec![Stockade]);
+        player.coins = 4;
+        let public_players = players_with_resources(vec![LumberYard], vec![LumberYard]);
+        let options = player.options_for_wonder_stage(&Stockade, &visible_game(&public_players), None);
+        assert_eq!(1, options.actions.len());
+        assert!(matches!(options.actions[0], Action::Wonder(Stockade, _)));
+    }
+
+    #[test]
+    fn options_for_wonder_stage_returns_nothing_if_all_stages_built() {
+        let mut player = new_player(vec![Stockade]);
+        player.built_wonder_stages = vec![Some(Altar), Some(Baths), Some(Theater)];
+        assert_eq!(
+            0,
+            player
+                .options_for_wonder_stage(&Stockade, &visible_game(&players()), None)
+                .actions
+                .len()
+        );
+    }
+
+    #[test]
+    fn can_play_returns_false_if_wonder_stage_not_affordable() {
+        // The first stage of the Colossus of Rhodes (side A) requires 2 wood, which we don't have.
+        let player = new_player(vec![Stockade]);
+        assert_eq!(
+            false,
+            player.can_play(
+                &Action::Wonder(Stockade, Borrowing::no_borrowing()),
+                &visible_game(&players())
+            )
+        );
+    }
+
+    #[test]
+    fn do_action_builds_wonder_stage_and_pays_neighbours() {
+        let mut player = new_player(vec![Stockade]);
+        player.coins = 4;
+        let mut coin_transfers = vec![];
+        let public_players = players_with_resources(vec![LumberYard], vec![LumberYard]);
+        assert_eq!(
+            Ok(()),
+            player.do_action(
+                &Action::Wonder(
+                    Stockade,
+                    Borrowing::new(
+                        vec![Borrow::new(LumberYard, Resource::Wood)],
+                        vec![Borrow::new(LumberYard, Resource::Wood)]
+                    )
+                ),
+                &visible_game(&public_players),
+                &mut vec![],
+                &mut coin_transfers,
+            )
+        );
+        assert_eq!(vec![Some(Stockade)], player.built_wonder_stages);
+        assert_eq!(0, player.built_structures.len());
+        assert_eq!(0, player.hand.len());
+        assert_eq!(0, player.coins);
+        assert_eq!(
+            vec![
+                CoinTransfer {
+                    from: 1,
+                    to: 2,
+                    coins: 2
+                },
+                CoinTransfer {
+                    from: 1,
+                    to: 0,
+                    coins: 2
+                }
+            ],
+            coin_transfers
+        );
+    }
+
+    #[test]
+    fn shields_includes_red_cards_and_wonder_stages() {
+        // The second stage of the Colossus of Rhodes (side A) provides 2 shields.
+        let mut player = new_player(vec![Barracks]);
+        build(&mut player, Barracks);
+        assert_eq!(1, player.shields());
+        player.built_wonder_stages = vec![Some(Altar), Some(Baths)];
+        assert_eq!(3, player.shields());
+    }
+
+    #[test]
+    fn resolve_conflict_awards_tokens_depending_on_age() {
+        let mut player = new_player(vec![Barracks]);
+        build(&mut player, Barracks);
+        player.resolve_conflict(0, &Age::First);
+        player.resolve_conflict(1, &Age::Second);
+        player.resolve_conflict(2, &Age::Second);
+        player.resolve_conflict(0, &Age::Third);
+        assert_eq!(vec![1, -1, 5], player.military_tokens);
+    }
+
+    #[test]
+    fn do_action_pays_out_coins_when_building_tavern() {
+        let mut player = new_player(vec![Tavern]);
+        assert_eq!(true, build(&mut player, Tavern));
+        assert_eq!(8, player.coins);
+    }
+
+    #[test]
+    fn do_action_pays_out_coins_for_neighbours_cards() {
+        // Vineyard pays 1 coin per brown card built by the player and their neighbours.
+        let mut player = new_player(vec![Vineyard]);
+        player.built_structures.push(StonePit);
+        let public_players = players_with_resources(vec![LumberYard, Loom1], vec![ClayPool, OreVein]);
+        assert_eq!(
+            Ok(()),
+            player.do_action(
+                &Action::Build(Vineyard, Borrowing::no_borrowing()),
+                &visible_game(&public_players),
+                &mut vec![],
+                &mut vec![],
+            )
+        );
+        assert_eq!(7, player.coins);
+    }
+
+    #[test]
+    fn do_action_pays_out_coins_counting_the_built_card() {
+        // Lighthouse pays 1 coin per yellow card built by the player, including itself.
+        let mut player = new_player(vec![Lighthouse]);
+        player.built_structures = vec![Tavern, StonePit, Glassworks1];
+        assert_eq!(true, build(&mut player, Lighthouse));
+        assert_eq!(5, player.coins);
+    }
+
+    #[test]
+    fn do_action_pays_out_coins_when_building_wonder_stage() {
+        // The second stage of the Temple of Artemis (side A) pays 9 coins.
+        let mut player = Player::new(WonderType::TempleOfArtemis, WonderSide::A);
+        player.swap_hand(vec![Altar]);
+        player.built_wonder_stages.push(Some(Baths));
+        player.built_structures = vec![LumberYard, TreeFarm];
+        assert_eq!(
+            Ok(()),
+            player.do_action(
+                &Action::Wonder(Altar, Borrowing::no_borrowing()),
+                &visible_game(&players()),
+                &mut vec![],
+                &mut vec![],
+            )
+        );
+        assert_eq!(12, player.coins);
+    }
+
+    #[test]
+    fn build_from_discard_builds_card_for_free() {
+        let mut player = new_player(vec![]);
+        assert_eq!(true, player.build_from_discard(&Tavern, &visible_game(&players())));
+        assert_eq!(vec![Tavern], player.built_structures);
+        assert_eq!(8, player.coins);
+    }
+
+    #[test]
+    fn build_from_discard_returns_false_if_already_built() {
+        let mut player = new_player(vec![]);
+        player.built_structures.push(Tavern);
+        assert_eq!(false, player.build_from_discard(&Tavern, &visible_game(&players())));
+        assert_eq!(vec![Tavern], player.built_structures);
+    }
+
+    #[test]
+    fn options_for_card_includes_free_build() {
+        let player = zeus_player(vec![Temple]);
+        let options = player.options_for_card(&Temple, &visible_game(&players()), None);
+        assert_eq!(vec![Action::FreeBuild(Temple)], options.actions);
+    }
+
+    #[test]
+    fn options_for_card_offers_free_build_after_borrowing() {
+        let player = zeus_player(vec![Baths]);
+        let options = player.options_for_card(
+            &Baths,
+            &visible_game(&players_with_resources(vec![StonePit], vec![])),
+            None,
+        );
+        assert_eq!(2, options.actions.len());
+        assert!(matches!(options.actions[0], Action::Build(Baths, _)));
+        assert_eq!(Action::FreeBuild(Baths), options.actions[1]);
+    }
+
+    #[test]
+    fn options_for_card_does_not_offer_free_build_for_free_cards() {
+        let player = zeus_player(vec![LumberYard]);
+        let options = player.options_for_card(&LumberYard, &visible_game(&players()), None);
+        assert_eq!(
+            vec![Action::Build(LumberYard, Borrowing::no_borrowing())],
+            options.actions
+        );
+    }
+
+    #[test]
+    fn options_for_card_excludes_free_build_without_power() {
+        let player = new_player(vec![Temple]);
+        assert_eq!(
+            false,
+            player
+                .options_for_card(&Temple, &visible_game(&players()), None)
+                .possible()
+        );
+    }
+
+    #[test]
+    fn do_action_free_build_uses_free_build_for_the_age() {
+        let mut player = zeus_player(vec![Temple, Baths]);
+        assert_eq!(
+            Ok(()),
+            player.do_action(
+                &Action::FreeBuild(Temple),
+                &visible_game(&players()),
+                &mut vec![],
+                &mut vec![]
+            )
+        );
+        assert_eq!(vec![Temple], player.built_structures);
+        assert_eq!(3, player.coins);
+        assert_eq!(false, player.free_build_available());
+        assert_eq!(
+            false,
+            player.can_play(&Action::FreeBuild(Baths), &visible_game(&players()))
+        );
+
+        player.reset_free_build();
+        assert_eq!(
+            true,
+            player.can_play(&Action::FreeBuild(Baths), &visible_game(&players()))
+        );
+    }
+
+    #[test]
+    fn can_play_returns_false_for_free_build_without_power() {
+        let player = new_player(vec![Temple]);
+        assert_eq!(
+            false,
+            player.can_play(&Action::FreeBuild(Temple), &visible_game(&players()))
+        );
+    }
+
+    #[test]
+    fn check_action_returns_reason_action_is_illegal() {
+        let player = new_player(vec![Temple]);
+        let public_players = players();
+        let visible_game = visible_game(&public_players);
+        assert_eq!(
+            Err(IllegalActionReason::CardNotInHand),
+            player.check_action(&Action::Discard(Baths), &visible_game)
+        );
+        assert_eq!(
+            Err(IllegalActionReason::CannotAfford),
+            player.check_action(&Action::Build(Temple, Borrowing::no_borrowing()), &visible_game)
+        );
+        assert_eq!(
+            Err(IllegalActionReason::NoFreeBuild),
+            player.check_action(&Action::FreeBuild(Temple), &visible_game)
+        );
+        assert_eq!(Ok(()), player.check_action(&Action::Discard(Temple), &visible_game));
+    }
+
     #[test]
     fn new_public_player() {
         let player = new_player(vec![LumberYard]);
         let public_player = PublicPlayer::new(&player);
         assert_eq!(player.wonder, public_player.wonder);
         assert_eq!(player.built_structures, public_player.built_structures);
+        assert_eq!(
+            player.built_wonder_stages.len() as u32,
+            public_player.wonder_stages_built
+        );
+        assert_eq!(player.military_tokens, public_player.military_tokens);
         assert_eq!(player.coins, public_player.coins);
+        assert_eq!(player.free_build_used, public_player.free_build_used);
+        assert_eq!(player.copied_guild, public_player.copied_guild);
+    }
+
+    #[test]
+    fn new_from_public_keeps_free_build_used() {
+        let mut player = new_player(vec![]);
+        player.free_build_used = true;
+        let player = Player::new_from_public(&PublicPlayer::new(&player), vec![]);
+        assert_eq!(true, player.free_build_used());
+    }
+
+    fn score_for_structures(built_structures: Vec<Card>) -> ScoreBreakdown {
+        let mut player = new_player(vec![]);
+        player.coins = 0;
+        player.built_structures = built_structures;
+        let public_players = players();
+        player.score(&public_players[2], &public_players[0])
     }
 
     fn new_player(hand: Vec<Card>) -> Player {
@@ -1037,9 +2180,35 @@ mod tests {
         player
     }
 
-    fn visible_game(public_players: &[PublicPlayer]) -> VisibleGame {
+    /// Creates a player with the first two stages of the Statue of Zeus (side A) built, so they have a free build
+    /// available.
+    fn zeus_player(hand: Vec<Card>) -> Player {
+        let mut player = Player::new(WonderType::StatueOfZeus, WonderSide::A);
+        player.built_wonder_stages = vec![None, None];
+        player.swap_hand(hand);
+        player
+    }
+
+    /// Creates a player with all stages of the Statue of Zeus (side B) built, so they can copy a neighbour's guild.
+    fn olympia_b_player() -> Player {
+        let mut player = Player::new(WonderType::StatueOfZeus, WonderSide::B);
+        player.built_wonder_stages = vec![None, None, None];
+        player
+    }
+
+    /// Creates a player with the first two stages of the Lighthouse of Alexandria (side B) built, which provide a
+    /// choice of raw material and a choice of manufactured good.
+    fn lighthouse_b_player(hand: Vec<Card>) -> Player {
+        let mut player = Player::new(WonderType::LighthouseOfAlexandria, WonderSide::B);
+        player.built_wonder_stages = vec![None, None];
+        player.swap_hand(hand);
+        player
+    }
+
+    fn visible_game(public_players: &[PublicPlayer]) -> VisibleGame<'_> {
         VisibleGame {
             public_players,
+            history: &[],
             player_index: 1,
             turn: 0,
         }
@@ -1057,7 +2226,11 @@ mod tests {
                     wonder_side: WonderSide::A,
                 },
                 built_structures: right,
+                wonder_stages_built: 0,
+                military_tokens: vec![],
                 coins: 0,
+                free_build_used: false,
+                copied_guild: None,
             },
             PublicPlayer {
                 wonder: WonderBoard {
@@ -1065,7 +2238,11 @@ mod tests {
                     wonder_side: WonderSide::A,
                 },
                 built_structures: vec![],
+                wonder_stages_built: 0,
+                military_tokens: vec![],
                 coins: 0,
+                free_build_used: false,
+                copied_guild: None,
             },
             PublicPlayer {
                 wonder: WonderBoard {
@@ -1073,20 +2250,23 @@ mod tests {
                     wonder_side: WonderSide::A,
                 },
                 built_structures: left,
+                wonder_stages_built: 0,
+                military_tokens: vec![],
                 coins: 0,
+                free_build_used: false,
+                copied_guild: None,
             },
         ]
     }
 
     fn build(player: &mut Player, card: Card) -> bool {
-        let mut left_neighbour = new_player(vec![]);
-        let mut right_neighbour = new_player(vec![]);
-        player.do_action(
-            &Action::Build(card, Borrowing::no_borrowing()),
-            &visible_game(&players()),
-            &mut left_neighbour,
-            &mut right_neighbour,
-            &mut vec![],
-        )
+        player
+            .do_action(
+                &Action::Build(card, Borrowing::no_borrowing()),
+                &visible_game(&players()),
+                &mut vec![],
+                &mut vec![],
+            )
+            .is_ok()
     }
 }
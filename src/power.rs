@@ -8,11 +8,13 @@ use itertools::Itertools;
 use strum_macros::EnumIter;
 
 use crate::card::{Card, Colour};
+use crate::player::PublicPlayer;
 use crate::resources::Resource;
 use crate::utils::plural;
 
 /// Represents what a card or a wonder stage does for a player (for example, delivers victory points, or gives access to
 /// a scientific structure).
+#[allow(dead_code)]
 pub enum Power {
     /// Produces resources that are purchasable by a neighbour (ie. brown and grey cards).
     PurchasableProducer(ProducedResources),
@@ -35,6 +37,17 @@ pub enum Power {
     /// Provides coins and/or victory points based on the number of game items a player or his neighbours have. For
     /// example, provides victory points based on the number of brown cards the player's neighbours have.
     PerGameItemRewards(Vec<PerGameItemReward>),
+    /// Allows the player to build a card from the discard pile for free at the end of the turn (the Mausoleum of
+    /// Halicarnassus).
+    BuildFromDiscard,
+    /// Allows the player to play the seventh card of each age rather than discarding it (the Hanging Gardens of
+    /// Babylon, side B).
+    PlaySeventhCard,
+    /// Allows the player to build one structure for free each age (the Statue of Zeus in Olympia, side A).
+    FreeBuildOncePerAge,
+    /// Allows the player to copy one guild built by a neighbour at the end of the game (the Statue of Zeus in Olympia,
+    /// side B).
+    CopyGuild,
 }
 
 impl Power {
@@ -100,6 +113,10 @@ impl Display for Power {
                 ),
                 Power::Shields(shields) => plural(*shields as i32, "shield"),
                 Power::PerGameItemRewards(_) => "Per game item thing (TODO)".to_string(), // TODO
+                Power::BuildFromDiscard => "Build a card from the discard pile for free".to_string(),
+                Power::PlaySeventhCard => "Play the seventh card of each age".to_string(),
+                Power::FreeBuildOncePerAge => "Build one structure per age for free".to_string(),
+                Power::CopyGuild => "Copy a neighbour's guild at the end of the game".to_string(),
             }
         )
     }
@@ -152,9 +169,32 @@ pub struct PerGameItemReward {
     pub points_per_thing: u32,
 }
 
+impl PerGameItemReward {
+    /// Returns the number of things counted by this reward for the player `me`, whose neighbours are `left` and
+    /// `right`.
+    pub fn count(&self, me: &PublicPlayer, left: &PublicPlayer, right: &PublicPlayer) -> u32 {
+        let count_for = |player: &PublicPlayer| {
+            player
+                .countable_game_items()
+                .into_iter()
+                .filter(|game_item| (self.game_item)(*game_item))
+                .count() as u32
+        };
+
+        let mut count = 0;
+        if self.me {
+            count += count_for(me);
+        }
+        if self.neighbours {
+            count += count_for(left) + count_for(right);
+        }
+        count
+    }
+}
+
 /// Something in the game that is "countable", such as the number of cards a player has built, or the number of Defeat
 /// Tokens they have.
-#[allow(dead_code)]
+#[derive(Copy, Clone)]
 pub enum CountableGameItem {
     CountableCard(Card),
     DefeatToken,
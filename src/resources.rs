@@ -2,9 +2,11 @@ use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::ops::SubAssign;
 
+use strum_macros::EnumString;
+
 use crate::utils::plural;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, EnumString)]
 pub enum Resource {
     Wood,
     Stone,
@@ -16,6 +18,14 @@ pub enum Resource {
     Papyrus,
 }
 
+impl Resource {
+    /// Returns `true` if this is a raw material (produced by brown cards), `false` if it's a manufactured good
+    /// (produced by grey cards).
+    pub fn is_raw_material(&self) -> bool {
+        matches!(self, Resource::Wood | Resource::Stone | Resource::Ore | Resource::Clay)
+    }
+}
+
 impl Display for Resource {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
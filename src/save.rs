@@ -0,0 +1,659 @@
+//! Saves the complete state of a game as text, and loads it back again.
+//!
+//! The saved text is made up of lines, each holding a key followed by zero or more values separated by spaces. Cards,
+//! wonders and wonder sides are written using their identifiers (eg. `TimberYard` rather than `Timber Yard`), and
+//! algorithms using their name (see [`PlayingAlgorithm::name`]). Each revealed action in the history is written as its
+//! turn, player index, whether it was a seventh card, the kind of action, the card (unless hidden) and any payments to
+//! neighbours as `<player index>:<coins>`. Each illegal action returned by an algorithm (see
+//! [`GameState::illegal_actions`]) is written as its turn, player index, the reason it was illegal, the kind of action,
+//! the card and any borrowing as `<L or R>:<card>:<resource>`. Each player's `rng` line holds how far their algorithm's
+//! random number generator has got (see [`PlayingAlgorithm::rng_position`]), and is empty for algorithms that make no
+//! random choices. For example:
+//!
+//! ```text
+//! version 1
+//! seed 42
+//! illegal_action_policy Retry 3
+//! turn 7
+//! discard Altar
+//! revealed 6 0 false Build Barracks 1:2
+//! revealed 6 1 false Discard
+//! ...
+//! illegal 6 2 CannotAfford Build Temple L:LumberYard:Wood
+//! ...
+//! player Random
+//! wonder ColossusOfRhodes A
+//! coins 5
+//! hand Baths Loom1
+//! built LumberYard StonePit
+//! stages TimberYard
+//! military 1
+//! free_build_used false
+//! copied_guild
+//! rng 1536
+//! player MonteCarlo
+//! ...
+//! ```
+//!
+//! Games are saved between turns (see [`Game::play_turn`]), and a loaded game plays on exactly as the original would
+//! have done, except that anything else an algorithm remembers isn't saved. For example, [`algorithms::monte_carlo`]
+//! forgets what it has deduced about other players' hands, so may choose differently until the next age.
+
+use std::fmt::Display;
+use std::iter::{Enumerate, Peekable};
+use std::str::{FromStr, Lines};
+
+use crate::action::{Action, Borrow, Borrowing, CoinTransfer, PublicAction, RevealedAction};
+use crate::algorithms;
+use crate::algorithms::PlayingAlgorithm;
+use crate::card::Card;
+use crate::error::GameError;
+use crate::game::{Game, GameState, IllegalAction, IllegalActionPolicy, Step};
+use crate::player::Player;
+use crate::wonder::{WonderBoard, WonderSide, WonderType};
+
+/// The version of the save format written by [`save`]. Increment when the format changes.
+const VERSION: u32 = 1;
+
+/// Written in place of a wonder stage card that isn't known.
+const UNKNOWN_CARD: &str = "?";
+
+/// Returns the complete state of the given game as text, which can be turned back into a game using [`load`]. Returns
+/// an error unless the game is between turns, waiting either to deal or for every player's action.
+pub fn save(game: &Game) -> Result<String, GameError> {
+    let state = game.state();
+    match state.step() {
+        Step::Deal
+        | Step::Actions {
+            seventh_card: false, ..
+        } => {}
+        _ => return Err(GameError::NotBetweenTurns),
+    }
+    let mut lines = vec![
+        line("version", &[VERSION]),
+        line("seed", &[state.seed()]),
+        policy_line(game.illegal_action_policy()),
+        line("turn", &[state.turn()]),
+        line("discard", &ids(state.discard_pile())),
+    ];
+    lines.extend(state.history().iter().map(revealed_line));
+    lines.extend(state.illegal_actions().iter().map(illegal_line));
+    for (player, algorithm) in state.players().iter().zip(game.algorithms()) {
+        let wonder = player.wonder();
+        let stages: Vec<String> = player
+            .built_wonder_stages()
+            .iter()
+            .map(|card| match card {
+                Some(card) => format!("{:?}", card),
+                None => String::from(UNKNOWN_CARD),
+            })
+            .collect();
+        lines.push(line("player", &[algorithm.name()]));
+        lines.push(line(
+            "wonder",
+            &[format!("{:?}", wonder.wonder_type), format!("{:?}", wonder.wonder_side)],
+        ));
+        lines.push(line("coins", &[player.coins()]));
+        lines.push(line("hand", &ids(player.hand())));
+        lines.push(line("built", &ids(player.built_structures())));
+        lines.push(line("stages", &stages));
+        lines.push(line("military", player.military_tokens()));
+        lines.push(line("free_build_used", &[player.free_build_used()]));
+        lines.push(line("copied_guild", &ids(player.copied_guild().iter())));
+        lines.push(line("rng", &algorithm.rng_position().iter().collect::<Vec<_>>()));
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Recreates a game from text written by [`save`]. The algorithms are created by name (see [`algorithms::by_name`]).
+pub fn load(text: &str) -> Result<Game, GameError> {
+    let mut reader = Reader {
+        lines: text.lines().enumerate().peekable(),
+        line_number: 0,
+    };
+
+    let version: u32 = reader.single("version")?;
+    if version != VERSION {
+        return Err(reader.error(format!("unsupported version {}", version)));
+    }
+    let seed = reader.single("seed")?;
+    let illegal_action_policy = reader.illegal_action_policy()?;
+    let turn = reader.single("turn")?;
+    let discard_pile = reader.list("discard")?;
+    let mut history = vec![];
+    while reader.next_key_is("revealed") {
+        history.push(reader.revealed_action()?);
+    }
+    let mut illegal_actions = vec![];
+    while reader.next_key_is("illegal") {
+        illegal_actions.push(reader.illegal_action()?);
+    }
+
+    let mut players = vec![];
+    let mut algorithms = vec![];
+    let mut rng_positions = vec![];
+    while reader.has_more() {
+        let algorithm = reader.algorithm()?;
+        let wonder = reader.wonder()?;
+        let coins = reader.single("coins")?;
+        let hand = reader.list("hand")?;
+        let built_structures = reader.list("built")?;
+        let built_wonder_stages = reader
+            .values("stages")?
+            .iter()
+            .map(|value| match *value {
+                UNKNOWN_CARD => Ok(None),
+                _ => reader.parse(value).map(Some),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if built_wonder_stages.len() > wonder.stage_count() as usize {
+            return Err(reader.error(format!(
+                "{} side {:?} has only {} stages",
+                wonder.name(),
+                wonder.wonder_side,
+                wonder.stage_count()
+            )));
+        }
+        let military_tokens = reader.list("military")?;
+        let free_build_used = reader.single("free_build_used")?;
+        let copied_guild = reader.optional("copied_guild")?;
+        rng_positions.push(reader.optional("rng")?);
+        players.push(Player::new_from_state(
+            wonder,
+            built_structures,
+            built_wonder_stages,
+            military_tokens,
+            coins,
+            hand,
+            free_build_used,
+            copied_guild,
+        ));
+        algorithms.push(algorithm);
+    }
+
+    let mut state = GameState::new(players, turn, discard_pile, history, seed)?;
+    state.set_illegal_actions(illegal_actions);
+    let mut game = Game::restore(state, algorithms, &rng_positions)?;
+    game.set_illegal_action_policy(illegal_action_policy);
+    Ok(game)
+}
+
+/// Returns a line of the saved text with the given key and values.
+fn line<T: Display>(key: &str, values: &[T]) -> String {
+    values
+        .iter()
+        .fold(String::from(key), |line, value| format!("{} {}", line, value))
+}
+
+/// Returns the identifier of each of the given cards, as understood by [`Card::from_str`].
+fn ids<'a>(cards: impl IntoIterator<Item = &'a Card>) -> Vec<String> {
+    cards.into_iter().map(|card| format!("{:?}", card)).collect()
+}
+
+/// Returns the line of the saved text for the given revealed action.
+fn revealed_line(revealed_action: &RevealedAction) -> String {
+    let mut values = vec![
+        revealed_action.turn.to_string(),
+        revealed_action.player_index.to_string(),
+        revealed_action.seventh_card.to_string(),
+    ];
+    match revealed_action.action {
+        PublicAction::Build(card) => values.extend(vec![String::from("Build"), format!("{:?}", card)]),
+        PublicAction::Wonder => values.push(String::from("Wonder")),
+        PublicAction::FreeBuild(card) => values.extend(vec![String::from("FreeBuild"), format!("{:?}", card)]),
+        PublicAction::Discard => values.push(String::from("Discard")),
+        PublicAction::BuildFromDiscard(card) => {
+            values.extend(vec![String::from("BuildFromDiscard"), format!("{:?}", card)])
+        }
+    }
+    for payment in &revealed_action.payments {
+        values.push(format!("{}:{}", payment.to, payment.coins));
+    }
+    line("revealed", &values)
+}
+
+/// Returns the line of the saved text for the given illegal action policy.
+fn policy_line(illegal_action_policy: IllegalActionPolicy) -> String {
+    match illegal_action_policy {
+        IllegalActionPolicy::Retry(max_attempts) => {
+            line("illegal_action_policy", &["Retry", &max_attempts.to_string()])
+        }
+        IllegalActionPolicy::ForceDiscard => line("illegal_action_policy", &["ForceDiscard"]),
+        IllegalActionPolicy::Abort => line("illegal_action_policy", &["Abort"]),
+    }
+}
+
+/// Returns the line of the saved text for the given illegal action.
+fn illegal_line(illegal_action: &IllegalAction) -> String {
+    let (kind, card, borrowing) = match &illegal_action.action {
+        Action::Build(card, borrowing) => ("Build", card, Some(borrowing)),
+        Action::Wonder(card, borrowing) => ("Wonder", card, Some(borrowing)),
+        Action::FreeBuild(card) => ("FreeBuild", card, None),
+        Action::Discard(card) => ("Discard", card, None),
+    };
+    let mut values = vec![
+        illegal_action.turn.to_string(),
+        illegal_action.player_index.to_string(),
+        format!("{:?}", illegal_action.reason),
+        String::from(kind),
+        format!("{:?}", card),
+    ];
+    if let Some(borrowing) = borrowing {
+        for (side, borrows) in &[("L", &borrowing.left), ("R", &borrowing.right)] {
+            values.extend(
+                borrows
+                    .iter()
+                    .map(|borrow| format!("{}:{:?}:{:?}", side, borrow.card, borrow.resource)),
+            );
+        }
+    }
+    line("illegal", &values)
+}
+
+/// Reads the lines of saved text in order, keeping track of the current line for error messages.
+struct Reader<'a> {
+    lines: Peekable<Enumerate<Lines<'a>>>,
+    /// The number, counting from 1, of the line most recently read.
+    line_number: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn has_more(&mut self) -> bool {
+        self.lines.peek().is_some()
+    }
+
+    /// Returns `true` if the next line has the given key.
+    fn next_key_is(&mut self, key: &str) -> bool {
+        matches!(self.lines.peek(), Some((_, line)) if line.split_whitespace().next() == Some(key))
+    }
+
+    /// Returns an error relating to the line most recently read.
+    fn error(&self, message: String) -> GameError {
+        GameError::InvalidSave {
+            line: self.line_number,
+            message,
+        }
+    }
+
+    /// Reads the next line, which must have the given key, and returns its values.
+    fn values(&mut self, key: &str) -> Result<Vec<&'a str>, GameError> {
+        let (index, line) = match self.lines.next() {
+            Some(next) => next,
+            None => {
+                self.line_number += 1;
+                return Err(self.error(format!("expected {}, found end of text", key)));
+            }
+        };
+        self.line_number = index + 1;
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some(word) if word == key => Ok(words.collect()),
+            Some(word) => Err(self.error(format!("expected {}, found {}", key, word))),
+            None => Err(self.error(format!("expected {}, found an empty line", key))),
+        }
+    }
+
+    fn parse<T: FromStr>(&self, value: &str) -> Result<T, GameError> {
+        value
+            .parse()
+            .map_err(|_| self.error(format!("invalid value {}", value)))
+    }
+
+    /// Reads a line with the given key and any number of values.
+    fn list<T: FromStr>(&mut self, key: &str) -> Result<Vec<T>, GameError> {
+        self.values(key)?.iter().map(|value| self.parse(value)).collect()
+    }
+
+    /// Reads a line with the given key and zero or one values.
+    fn optional<T: FromStr>(&mut self, key: &str) -> Result<Option<T>, GameError> {
+        let mut values: Vec<T> = self.list(key)?;
+        if values.len() > 1 {
+            return Err(self.error(format!("expected at most one value for {}", key)));
+        }
+        Ok(values.pop())
+    }
+
+    /// Reads a line with the given key and exactly one value.
+    fn single<T: FromStr>(&mut self, key: &str) -> Result<T, GameError> {
+        self.optional(key)?
+            .ok_or_else(|| self.error(format!("expected a value for {}", key)))
+    }
+
+    fn revealed_action(&mut self) -> Result<RevealedAction, GameError> {
+        let values = self.values("revealed")?;
+        if values.len() < 4 {
+            return Err(self.error(String::from("expected a turn, player, seventh card flag and action")));
+        }
+        let player_index = self.parse(values[1])?;
+        let (action, payments) = match (values[3], values.get(4)) {
+            ("Wonder", _) => (PublicAction::Wonder, &values[4..]),
+            ("Discard", _) => (PublicAction::Discard, &values[4..]),
+            ("Build", Some(card)) => (PublicAction::Build(self.parse(card)?), &values[5..]),
+            ("FreeBuild", Some(card)) => (PublicAction::FreeBuild(self.parse(card)?), &values[5..]),
+            ("BuildFromDiscard", Some(card)) => (PublicAction::BuildFromDiscard(self.parse(card)?), &values[5..]),
+            (action, _) => return Err(self.error(format!("invalid action {}", action))),
+        };
+        let payments = payments
+            .iter()
+            .map(|payment| match payment.split(':').collect::<Vec<&str>>().as_slice() {
+                [to, coins] => Ok(CoinTransfer {
+                    from: player_index,
+                    to: self.parse(to)?,
+                    coins: self.parse(coins)?,
+                }),
+                _ => Err(self.error(format!("invalid payment {}", payment))),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(RevealedAction {
+            turn: self.parse(values[0])?,
+            player_index,
+            action,
+            payments,
+            seventh_card: self.parse(values[2])?,
+        })
+    }
+
+    fn illegal_action_policy(&mut self) -> Result<IllegalActionPolicy, GameError> {
+        match self.values("illegal_action_policy")?.as_slice() {
+            ["Retry", max_attempts] => Ok(IllegalActionPolicy::Retry(self.parse(max_attempts)?)),
+            ["ForceDiscard"] => Ok(IllegalActionPolicy::ForceDiscard),
+            ["Abort"] => Ok(IllegalActionPolicy::Abort),
+            _ => Err(self.error(String::from(
+                "expected Retry and a number of attempts, ForceDiscard or Abort",
+            ))),
+        }
+    }
+
+    fn illegal_action(&mut self) -> Result<IllegalAction, GameError> {
+        let values = self.values("illegal")?;
+        if values.len() < 5 {
+            return Err(self.error(String::from("expected a turn, player, reason, action and card")));
+        }
+        let card = self.parse(values[4])?;
+        let mut borrowing = Borrowing::no_borrowing();
+        for borrow in &values[5..] {
+            match borrow.split(':').collect::<Vec<&str>>().as_slice() {
+                ["L", card, resource] => borrowing
+                    .left
+                    .push(Borrow::new(self.parse(card)?, self.parse(resource)?)),
+                ["R", card, resource] => borrowing
+                    .right
+                    .push(Borrow::new(self.parse(card)?, self.parse(resource)?)),
+                _ => return Err(self.error(format!("invalid borrow {}", borrow))),
+            }
+        }
+        let action = match values[3] {
+            "Build" => Action::Build(card, borrowing),
+            "Wonder" => Action::Wonder(card, borrowing),
+            "FreeBuild" => Action::FreeBuild(card),
+            "Discard" => Action::Discard(card),
+            action => return Err(self.error(format!("invalid action {}", action))),
+        };
+        Ok(IllegalAction {
+            player_index: self.parse(values[1])?,
+            turn: self.parse(values[0])?,
+            action,
+            reason: self.parse(values[2])?,
+        })
+    }
+
+    fn algorithm(&mut self) -> Result<Box<dyn PlayingAlgorithm>, GameError> {
+        let name: String = self.single("player")?;
+        algorithms::by_name(&name).ok_or_else(|| self.error(format!("unknown algorithm {}", name)))
+    }
+
+    fn wonder(&mut self) -> Result<WonderBoard, GameError> {
+        match self.values("wonder")?.as_slice() {
+            [wonder_type, wonder_side] => Ok(WonderBoard {
+                wonder_type: self.parse::<WonderType>(wonder_type)?,
+                wonder_side: self.parse::<WonderSide>(wonder_side)?,
+            }),
+            _ => Err(self.error(String::from("expected a wonder and a side"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::IllegalActionReason;
+    use crate::algorithms::random::Random;
+    use crate::resources::Resource;
+    use Card::*;
+
+    #[test]
+    fn load_restores_saved_game() {
+        let game = game();
+        let text = save(&game).unwrap();
+        let loaded = load(&text).unwrap();
+        assert_eq!(text, save(&loaded).unwrap());
+        assert_eq!(7, loaded.state().turn());
+        assert_eq!(42, loaded.state().seed());
+        assert_eq!(&vec![Altar], loaded.state().discard_pile());
+        assert_eq!(game.state().history(), loaded.state().history());
+        assert_eq!(game.state().illegal_actions(), loaded.state().illegal_actions());
+        let player = &loaded.state().players()[0];
+        assert_eq!(&vec![Baths, Loom1], player.hand());
+        assert_eq!(&vec![Some(TimberYard), None], player.built_wonder_stages());
+        assert_eq!(&vec![1, -1], player.military_tokens());
+        assert_eq!(Some(ScientistsGuild), player.copied_guild());
+        assert_eq!("Random", loaded.algorithms()[1].name());
+    }
+
+    #[test]
+    fn loaded_game_plays_the_same_as_the_original() {
+        let mut game = Game::new(random_algorithms(), 42).unwrap();
+        let mut loaded = load(&save(&game).unwrap()).unwrap();
+        assert_eq!(game.play().unwrap(), loaded.play().unwrap());
+    }
+
+    #[test]
+    fn game_loaded_mid_game_plays_the_same_as_the_original() {
+        let mut game = Game::new(random_algorithms(), 42).unwrap();
+        for _ in 0..8 {
+            game.play_turn().unwrap();
+        }
+        let text = save(&game).unwrap();
+        assert!(text.contains("turn 8\n"));
+        let mut loaded = load(&text).unwrap();
+        assert_eq!(text, save(&loaded).unwrap());
+        assert_eq!(game.play().unwrap(), loaded.play().unwrap());
+    }
+
+    #[test]
+    fn load_restores_illegal_action_policy() {
+        let mut game = game();
+        game.set_illegal_action_policy(IllegalActionPolicy::Retry(3));
+        let text = save(&game).unwrap();
+        assert!(text.contains("illegal_action_policy Retry 3\n"));
+        assert_eq!(
+            IllegalActionPolicy::Retry(3),
+            load(&text).unwrap().illegal_action_policy()
+        );
+    }
+
+    #[test]
+    fn save_writes_one_line_per_field() {
+        let text = save(&game()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            vec![
+                "version 1",
+                "seed 42",
+                "illegal_action_policy ForceDiscard",
+                "turn 7",
+                "discard Altar",
+                "revealed 6 0 false Build Barracks 1:2",
+                "revealed 6 1 true Wonder",
+                "illegal 6 2 CannotAfford Build Temple L:LumberYard:Wood R:Loom1:Loom",
+                "player Random"
+            ],
+            lines[..9]
+        );
+        assert!(lines.contains(&"stages TimberYard ?"));
+        assert!(lines.contains(&"military 1 -1"));
+        assert!(lines.contains(&"copied_guild"));
+        assert!(lines.contains(&"rng 0"));
+    }
+
+    #[test]
+    fn save_returns_error_unless_between_turns() {
+        let mut players = game().state().players().to_vec();
+        // The second stage of the Hanging Gardens of Babylon (side B) lets the player play the seventh card.
+        players[0] = Player::new_from_state(
+            WonderBoard {
+                wonder_type: WonderType::HangingGardensOfBabylon,
+                wonder_side: WonderSide::B,
+            },
+            vec![],
+            vec![None, None],
+            vec![],
+            0,
+            vec![Tavern, Altar],
+            false,
+            None,
+        );
+        let mut state = GameState::new(players, 5, vec![], vec![], 42).unwrap();
+        let text = save(&Game::restore(state.clone(), random_algorithms(), &[]).unwrap()).unwrap();
+        assert_eq!(text, save(&load(&text).unwrap()).unwrap());
+
+        state
+            .apply(vec![
+                Action::Discard(Tavern),
+                Action::Discard(Baths),
+                Action::Discard(Baths),
+            ])
+            .unwrap();
+        let mut game = Game::restore(state, random_algorithms(), &[]).unwrap();
+        assert!(matches!(game.state().step(), Step::Actions { seventh_card: true, .. }));
+        assert_eq!(Err(GameError::NotBetweenTurns), save(&game));
+
+        game.play().unwrap();
+        assert_eq!(Err(GameError::NotBetweenTurns), save(&game));
+    }
+
+    #[test]
+    fn load_returns_error_for_unknown_card() {
+        let text = save(&game())
+            .unwrap()
+            .replace("hand Baths Loom1", "hand Baths Plumbing");
+        assert_eq!(
+            Err(GameError::InvalidSave {
+                line: 12,
+                message: String::from("invalid value Plumbing")
+            }),
+            load(&text).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn load_returns_error_for_unknown_algorithm() {
+        let text = save(&game()).unwrap().replacen("player Random", "player Oracle", 1);
+        assert_eq!(
+            Err(GameError::InvalidSave {
+                line: 9,
+                message: String::from("unknown algorithm Oracle")
+            }),
+            load(&text).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn load_returns_error_for_missing_field() {
+        let text = save(&game()).unwrap().replacen("coins 5\n", "", 1);
+        assert_eq!(
+            Err(GameError::InvalidSave {
+                line: 11,
+                message: String::from("expected coins, found hand")
+            }),
+            load(&text).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn load_returns_error_for_too_many_wonder_stages() {
+        let text = save(&game())
+            .unwrap()
+            .replacen("stages TimberYard ?", "stages TimberYard ? ? ?", 1);
+        assert_eq!(
+            Err(GameError::InvalidSave {
+                line: 14,
+                message: String::from("The Colossus of Rhodes side A has only 3 stages")
+            }),
+            load(&text).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn load_returns_error_for_unsupported_version() {
+        let text = save(&game()).unwrap().replace("version 1", "version 2");
+        assert!(matches!(load(&text), Err(GameError::InvalidSave { line: 1, .. })));
+    }
+
+    fn random_algorithms() -> Vec<Box<dyn PlayingAlgorithm>> {
+        vec![
+            Box::new(Random::default()),
+            Box::new(Random::default()),
+            Box::new(Random::default()),
+        ]
+    }
+
+    fn game() -> Game {
+        let players = vec![
+            (WonderType::ColossusOfRhodes, Some(ScientistsGuild)),
+            (WonderType::PyramidsOfGiza, None),
+            (WonderType::StatueOfZeus, None),
+        ]
+        .into_iter()
+        .map(|(wonder_type, copied_guild)| {
+            Player::new_from_state(
+                WonderBoard {
+                    wonder_type,
+                    wonder_side: WonderSide::A,
+                },
+                vec![LumberYard, StonePit],
+                vec![Some(TimberYard), None],
+                vec![1, -1],
+                5,
+                vec![Baths, Loom1],
+                false,
+                copied_guild,
+            )
+        })
+        .collect();
+        let history = vec![
+            RevealedAction {
+                turn: 6,
+                player_index: 0,
+                action: PublicAction::Build(Barracks),
+                payments: vec![CoinTransfer {
+                    from: 0,
+                    to: 1,
+                    coins: 2,
+                }],
+                seventh_card: false,
+            },
+            RevealedAction {
+                turn: 6,
+                player_index: 1,
+                action: PublicAction::Wonder,
+                payments: vec![],
+                seventh_card: true,
+            },
+        ];
+        let mut state = GameState::new(players, 7, vec![Altar], history, 42).unwrap();
+        state.set_illegal_actions(vec![IllegalAction {
+            player_index: 2,
+            turn: 6,
+            action: Action::Build(
+                Temple,
+                Borrowing::new(
+                    vec![Borrow::new(LumberYard, Resource::Wood)],
+                    vec![Borrow::new(Loom1, Resource::Loom)],
+                ),
+            ),
+            reason: IllegalActionReason::CannotAfford,
+        }]);
+        Game::restore(state, random_algorithms(), &[]).unwrap()
+    }
+}
@@ -0,0 +1,81 @@
+//! The end of game score of a player, broken down into the categories of the official score pad.
+
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use crate::utils::plural;
+
+/// The end of game score of a player, broken down into the seven categories of the official score pad.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct ScoreBreakdown {
+    /// Victory and defeat tokens from military conflicts.
+    pub military: i32,
+    /// One point for every three coins.
+    pub treasury: i32,
+    /// Victory points from built wonder stages.
+    pub wonders: i32,
+    /// Victory points from civilian (ie. blue) structures.
+    pub civilian: i32,
+    /// Victory points from commercial (ie. yellow) structures.
+    pub commercial: i32,
+    /// Victory points from guilds (ie. purple cards).
+    pub guilds: i32,
+    /// Victory points from scientific symbols, whether on green cards, guilds or wonder stages.
+    pub science: i32,
+}
+
+impl ScoreBreakdown {
+    /// Returns the total score across all categories.
+    pub fn total(&self) -> i32 {
+        self.military + self.treasury + self.wonders + self.civilian + self.commercial + self.guilds + self.science
+    }
+}
+
+/// Example formatting: `25 points (military 3, treasury 2, wonders 10, civilian 5, commercial 0, guilds 0, science 5)`
+impl Display for ScoreBreakdown {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (military {}, treasury {}, wonders {}, civilian {}, commercial {}, guilds {}, science {})",
+            plural(self.total(), "point"),
+            self.military,
+            self.treasury,
+            self.wonders,
+            self.civilian,
+            self.commercial,
+            self.guilds,
+            self.science
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn total_sums_all_categories() {
+        let score = ScoreBreakdown {
+            military: -1,
+            treasury: 2,
+            wonders: 3,
+            civilian: 4,
+            commercial: 5,
+            guilds: 6,
+            science: 7,
+        };
+        assert_eq!(26, score.total());
+    }
+
+    #[test]
+    fn display_includes_total_and_categories() {
+        let score = ScoreBreakdown {
+            military: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            "1 point (military 1, treasury 0, wonders 0, civilian 0, commercial 0, guilds 0, science 0)",
+            score.to_string()
+        );
+    }
+}
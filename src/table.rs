@@ -1,5 +1,7 @@
 //! Makes it easier to print text-based tables with lined-up columns.
 
+use crate::error::GameError;
+
 pub struct Table {
     rows: Vec<Vec<String>>,
     num_columns: usize,
@@ -19,12 +21,16 @@ impl Table {
         }
     }
 
-    /// Adds a new row.
-    pub fn add(&mut self, row: Vec<String>) {
+    /// Adds a new row. Returns an error if the row doesn't have the same number of columns as the header.
+    pub fn add(&mut self, row: Vec<String>) -> Result<(), GameError> {
         if row.len() != self.num_columns {
-            panic!("Row has wrong number of columns");
+            return Err(GameError::WrongNumberOfColumns {
+                expected: self.num_columns,
+                actual: row.len(),
+            });
         }
-        self.rows.push(row)
+        self.rows.push(row);
+        Ok(())
     }
 
     /// Prints the table to stdout. Each line will be prefixed with the given string, and each column will be separated
@@ -51,9 +57,11 @@ mod tests {
     use super::*;
 
     #[test]
-    #[should_panic(expected = "Row has wrong number of columns")]
-    fn add_panics_if_wrong_number_of_columns() {
+    fn add_returns_error_if_wrong_number_of_columns() {
         let mut table = Table::new(vec![String::from("col1")]);
-        table.add(vec![])
+        assert_eq!(
+            Err(GameError::WrongNumberOfColumns { expected: 1, actual: 0 }),
+            table.add(vec![])
+        );
     }
 }
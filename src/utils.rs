@@ -1,3 +1,6 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
 /// Helps write correct plurals.
 ///
 /// # Examples
@@ -14,3 +17,21 @@ pub fn plural(count: i32, word: &str) -> String {
         format!("{} {}s", count, word)
     }
 }
+
+/// Creates a random number generator for one of several independent streams of random numbers derived from a single
+/// seed. The same `seed` and `stream` always give the same sequence of numbers, while different streams give unrelated
+/// sequences. This allows everything random in a game to be reproduced from one seed, without the choices made using
+/// one stream affecting the numbers generated by another.
+///
+/// A named generator (ChaCha with 8 rounds) is used rather than [`rand::rngs::StdRng`], whose algorithm may change
+/// between versions of `rand`, so that a seed gives the same game whichever version is used.
+pub fn derive_rng(seed: u64, stream: u64) -> ChaCha8Rng {
+    let mut bytes = [0; 32];
+    bytes[..8].copy_from_slice(&seed.to_le_bytes());
+    bytes[8..16].copy_from_slice(&stream.to_le_bytes());
+    let mut rng = ChaCha8Rng::from_seed(bytes);
+    // Explicitly start at the beginning of the stream. This doesn't change the numbers generated, but a generator that
+    // hasn't been positioned or used yet can't report its position (see [`ChaCha8Rng::get_word_pos`]).
+    rng.set_word_pos(0);
+    rng
+}
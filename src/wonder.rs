@@ -1,7 +1,11 @@
+use lazy_static::lazy_static;
+use strum_macros::{EnumIter, EnumString};
+
+use crate::error::GameError;
+use crate::power::{Power, ProducedResources, ScienceItem};
 use crate::resources::{Cost, Resource};
-use strum_macros::EnumIter;
 
-#[derive(Debug, EnumIter, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, EnumIter, EnumString, Copy, Clone, Eq, PartialEq, Hash)]
 #[allow(dead_code)]
 pub enum WonderType {
     ColossusOfRhodes,
@@ -13,7 +17,7 @@ pub enum WonderType {
     PyramidsOfGiza,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, EnumIter, EnumString, Copy, Clone, Eq, PartialEq)]
 #[allow(dead_code)]
 pub enum WonderSide {
     A,
@@ -63,30 +67,278 @@ impl WonderBoard {
         self.wonder_type.starting_resource()
     }
 
-    pub fn cost(&self, position: u32) -> Cost {
-        match (&self.wonder_type, &self.wonder_side, position) {
-            (WonderType::ColossusOfRhodes, WonderSide::A, 0) => Cost::wood(2),
-            (WonderType::ColossusOfRhodes, WonderSide::A, 1) => Cost::clay(3),
-            (WonderType::ColossusOfRhodes, WonderSide::A, 2) => Cost::ore(4),
-            (WonderType::ColossusOfRhodes, WonderSide::A, _) => panic!(),
+    /// Returns the stages of this side of the wonder, in the order they must be built.
+    pub fn stages(&self) -> &Vec<WonderStage> {
+        match (&self.wonder_type, &self.wonder_side) {
+            (WonderType::ColossusOfRhodes, WonderSide::A) => &COLOSSUS_OF_RHODES_A,
+            (WonderType::ColossusOfRhodes, WonderSide::B) => &COLOSSUS_OF_RHODES_B,
+            (WonderType::LighthouseOfAlexandria, WonderSide::A) => &LIGHTHOUSE_OF_ALEXANDRIA_A,
+            (WonderType::LighthouseOfAlexandria, WonderSide::B) => &LIGHTHOUSE_OF_ALEXANDRIA_B,
+            (WonderType::TempleOfArtemis, WonderSide::A) => &TEMPLE_OF_ARTEMIS_A,
+            (WonderType::TempleOfArtemis, WonderSide::B) => &TEMPLE_OF_ARTEMIS_B,
+            (WonderType::HangingGardensOfBabylon, WonderSide::A) => &HANGING_GARDENS_OF_BABYLON_A,
+            (WonderType::HangingGardensOfBabylon, WonderSide::B) => &HANGING_GARDENS_OF_BABYLON_B,
+            (WonderType::StatueOfZeus, WonderSide::A) => &STATUE_OF_ZEUS_A,
+            (WonderType::StatueOfZeus, WonderSide::B) => &STATUE_OF_ZEUS_B,
+            (WonderType::MausoleumOfHalicarnassus, WonderSide::A) => &MAUSOLEUM_OF_HALICARNASSUS_A,
+            (WonderType::MausoleumOfHalicarnassus, WonderSide::B) => &MAUSOLEUM_OF_HALICARNASSUS_B,
+            (WonderType::PyramidsOfGiza, WonderSide::A) => &PYRAMIDS_OF_GIZA_A,
+            (WonderType::PyramidsOfGiza, WonderSide::B) => &PYRAMIDS_OF_GIZA_B,
+        }
+    }
 
-            (WonderType::ColossusOfRhodes, WonderSide::B, 0) => Cost::stone(3),
-            (WonderType::ColossusOfRhodes, WonderSide::B, 1) => Cost::ore(4),
-            (WonderType::ColossusOfRhodes, WonderSide::B, _) => panic!(),
+    /// Returns the number of stages this side of the wonder has.
+    pub fn stage_count(&self) -> u32 {
+        self.stages().len() as u32
+    }
 
-            (WonderType::LighthouseOfAlexandria, WonderSide::A, 0) => Cost::stone(2),
-            (WonderType::LighthouseOfAlexandria, WonderSide::A, 1) => Cost::ore(2),
-            (WonderType::LighthouseOfAlexandria, WonderSide::A, 2) => Cost::glass(2),
-            (WonderType::LighthouseOfAlexandria, WonderSide::A, _) => panic!(),
+    /// Returns the cost of the wonder stage at the given 0-based position, or an error if there is no such stage.
+    pub fn cost(&self, position: u32) -> Result<&Cost, GameError> {
+        self.stages()
+            .get(position as usize)
+            .map(|stage| &stage.cost)
+            .ok_or(GameError::InvalidWonderStage(*self, position))
+    }
 
-            (WonderType::LighthouseOfAlexandria, WonderSide::B, 0) => Cost::clay(2),
-            (WonderType::LighthouseOfAlexandria, WonderSide::B, 1) => Cost::wood(2),
-            (WonderType::LighthouseOfAlexandria, WonderSide::B, 2) => Cost::stone(3),
-            (WonderType::LighthouseOfAlexandria, WonderSide::B, _) => panic!(),
+    /// Returns the powers of the wonder stage at the given 0-based position, or an error if there is no such stage.
+    pub fn powers(&self, position: u32) -> Result<&Vec<Power>, GameError> {
+        self.stages()
+            .get(position as usize)
+            .map(|stage| &stage.powers)
+            .ok_or(GameError::InvalidWonderStage(*self, position))
+    }
+}
+
+/// A single stage of a wonder: what it costs to build and what it gives the player once built.
+pub struct WonderStage {
+    pub cost: Cost,
+    /// Most stages have a single power, but some (for example, on the B side of the Colossus of Rhodes) have several.
+    pub powers: Vec<Power>,
+}
+
+impl WonderStage {
+    fn new(cost: Cost, powers: Vec<Power>) -> WonderStage {
+        WonderStage { cost, powers }
+    }
+}
+
+/// Convenience function returning the power of the Lighthouse of Alexandria stages that produce a raw material.
+fn raw_material_choice() -> Power {
+    Power::Producer(ProducedResources::Choice(vec![
+        Resource::Wood,
+        Resource::Stone,
+        Resource::Ore,
+        Resource::Clay,
+    ]))
+}
 
-            _ => todo!(),
+/// Convenience function returning the power of the Lighthouse of Alexandria stage that produces a manufactured good.
+fn manufactured_good_choice() -> Power {
+    Power::Producer(ProducedResources::Choice(vec![
+        Resource::Glass,
+        Resource::Loom,
+        Resource::Papyrus,
+    ]))
+}
+
+/// Convenience function returning the power of the Hanging Gardens of Babylon stages that provide any science symbol.
+fn any_science_item() -> Power {
+    Power::Science(vec![ScienceItem::Compass, ScienceItem::Cog, ScienceItem::Tablet])
+}
+
+lazy_static! {
+    static ref COLOSSUS_OF_RHODES_A: Vec<WonderStage> = vec![
+        WonderStage::new(Cost::wood(2), vec![Power::VictoryPoints(3)]),
+        WonderStage::new(Cost::clay(3), vec![Power::Shields(2)]),
+        WonderStage::new(Cost::ore(4), vec![Power::VictoryPoints(7)]),
+    ];
+    static ref COLOSSUS_OF_RHODES_B: Vec<WonderStage> = vec![
+        WonderStage::new(
+            Cost::stone(3),
+            vec![Power::Shields(1), Power::VictoryPoints(3), Power::Coins(3)]
+        ),
+        WonderStage::new(
+            Cost::ore(4),
+            vec![Power::Shields(1), Power::VictoryPoints(4), Power::Coins(4)]
+        ),
+    ];
+    static ref LIGHTHOUSE_OF_ALEXANDRIA_A: Vec<WonderStage> = vec![
+        WonderStage::new(Cost::stone(2), vec![Power::VictoryPoints(3)]),
+        WonderStage::new(Cost::ore(2), vec![raw_material_choice()]),
+        WonderStage::new(Cost::glass(2), vec![Power::VictoryPoints(7)]),
+    ];
+    static ref LIGHTHOUSE_OF_ALEXANDRIA_B: Vec<WonderStage> = vec![
+        WonderStage::new(Cost::clay(2), vec![raw_material_choice()]),
+        WonderStage::new(Cost::wood(2), vec![manufactured_good_choice()]),
+        WonderStage::new(Cost::stone(3), vec![Power::VictoryPoints(7)]),
+    ];
+    static ref TEMPLE_OF_ARTEMIS_A: Vec<WonderStage> = vec![
+        WonderStage::new(Cost::stone(2), vec![Power::VictoryPoints(3)]),
+        WonderStage::new(Cost::wood(2), vec![Power::Coins(9)]),
+        WonderStage::new(Cost::papyrus(2), vec![Power::VictoryPoints(7)]),
+    ];
+    static ref TEMPLE_OF_ARTEMIS_B: Vec<WonderStage> = vec![
+        WonderStage::new(Cost::stone(2), vec![Power::VictoryPoints(2), Power::Coins(4)]),
+        WonderStage::new(Cost::wood(2), vec![Power::VictoryPoints(3), Power::Coins(4)]),
+        WonderStage::new(
+            Cost {
+                glass: 1,
+                loom: 1,
+                papyrus: 1,
+                ..Default::default()
+            },
+            vec![Power::VictoryPoints(5), Power::Coins(4)]
+        ),
+    ];
+    static ref HANGING_GARDENS_OF_BABYLON_A: Vec<WonderStage> = vec![
+        WonderStage::new(Cost::clay(2), vec![Power::VictoryPoints(3)]),
+        WonderStage::new(Cost::wood(3), vec![any_science_item()]),
+        WonderStage::new(Cost::clay(4), vec![Power::VictoryPoints(7)]),
+    ];
+    static ref HANGING_GARDENS_OF_BABYLON_B: Vec<WonderStage> = vec![
+        WonderStage::new(
+            Cost {
+                clay: 1,
+                loom: 1,
+                ..Default::default()
+            },
+            vec![Power::VictoryPoints(3)]
+        ),
+        WonderStage::new(
+            Cost {
+                wood: 2,
+                glass: 1,
+                ..Default::default()
+            },
+            vec![Power::PlaySeventhCard]
+        ),
+        WonderStage::new(
+            Cost {
+                clay: 3,
+                papyrus: 1,
+                ..Default::default()
+            },
+            vec![any_science_item()]
+        ),
+    ];
+    static ref STATUE_OF_ZEUS_A: Vec<WonderStage> = vec![
+        WonderStage::new(Cost::wood(2), vec![Power::VictoryPoints(3)]),
+        WonderStage::new(Cost::stone(2), vec![Power::FreeBuildOncePerAge]),
+        WonderStage::new(Cost::ore(2), vec![Power::VictoryPoints(7)]),
+    ];
+    static ref STATUE_OF_ZEUS_B: Vec<WonderStage> = vec![
+        WonderStage::new(
+            Cost::wood(2),
+            vec![Power::BuyBrownClockwise, Power::BuyBrownAntiClockwise]
+        ),
+        WonderStage::new(Cost::stone(2), vec![Power::VictoryPoints(5)]),
+        WonderStage::new(
+            Cost {
+                ore: 2,
+                loom: 1,
+                ..Default::default()
+            },
+            vec![Power::CopyGuild]
+        ),
+    ];
+    static ref MAUSOLEUM_OF_HALICARNASSUS_A: Vec<WonderStage> = vec![
+        WonderStage::new(Cost::clay(2), vec![Power::VictoryPoints(3)]),
+        WonderStage::new(Cost::ore(3), vec![Power::BuildFromDiscard]),
+        WonderStage::new(Cost::loom(2), vec![Power::VictoryPoints(7)]),
+    ];
+    static ref MAUSOLEUM_OF_HALICARNASSUS_B: Vec<WonderStage> = vec![
+        WonderStage::new(Cost::ore(2), vec![Power::VictoryPoints(2), Power::BuildFromDiscard]),
+        WonderStage::new(Cost::clay(3), vec![Power::VictoryPoints(1), Power::BuildFromDiscard]),
+        WonderStage::new(
+            Cost {
+                glass: 1,
+                loom: 1,
+                papyrus: 1,
+                ..Default::default()
+            },
+            vec![Power::BuildFromDiscard]
+        ),
+    ];
+    static ref PYRAMIDS_OF_GIZA_A: Vec<WonderStage> = vec![
+        WonderStage::new(Cost::stone(2), vec![Power::VictoryPoints(3)]),
+        WonderStage::new(Cost::wood(3), vec![Power::VictoryPoints(5)]),
+        WonderStage::new(Cost::stone(4), vec![Power::VictoryPoints(7)]),
+    ];
+    static ref PYRAMIDS_OF_GIZA_B: Vec<WonderStage> = vec![
+        WonderStage::new(Cost::wood(2), vec![Power::VictoryPoints(3)]),
+        WonderStage::new(Cost::stone(3), vec![Power::VictoryPoints(5)]),
+        WonderStage::new(Cost::clay(3), vec![Power::VictoryPoints(5)]),
+        WonderStage::new(
+            Cost {
+                stone: 4,
+                papyrus: 1,
+                ..Default::default()
+            },
+            vec![Power::VictoryPoints(7)]
+        ),
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn every_wonder_side_has_stages() {
+        for wonder_type in WonderType::iter() {
+            for wonder_side in [WonderSide::A, WonderSide::B] {
+                let board = WonderBoard {
+                    wonder_type,
+                    wonder_side,
+                };
+                assert!(board.stage_count() >= 2);
+                for stage in board.stages() {
+                    assert!(!stage.powers.is_empty());
+                }
+            }
         }
     }
 
-    // TODO: power
+    #[test]
+    fn stage_count_varies_by_side() {
+        let board = |wonder_type, wonder_side| WonderBoard {
+            wonder_type,
+            wonder_side,
+        };
+        assert_eq!(3, board(WonderType::ColossusOfRhodes, WonderSide::A).stage_count());
+        assert_eq!(2, board(WonderType::ColossusOfRhodes, WonderSide::B).stage_count());
+        assert_eq!(4, board(WonderType::PyramidsOfGiza, WonderSide::B).stage_count());
+    }
+
+    #[test]
+    fn cost_returns_error_for_invalid_stage() {
+        let board = WonderBoard {
+            wonder_type: WonderType::StatueOfZeus,
+            wonder_side: WonderSide::B,
+        };
+        assert!(matches!(board.cost(3), Err(GameError::InvalidWonderStage(b, 3)) if b == board));
+    }
+
+    #[test]
+    fn powers_returns_error_for_invalid_stage() {
+        let board = WonderBoard {
+            wonder_type: WonderType::StatueOfZeus,
+            wonder_side: WonderSide::B,
+        };
+        assert!(matches!(board.powers(3), Err(GameError::InvalidWonderStage(b, 3)) if b == board));
+        assert!(matches!(board.powers(2).unwrap().as_slice(), [Power::CopyGuild]));
+    }
+
+    #[test]
+    fn cost_returns_stage_cost() {
+        let board = WonderBoard {
+            wonder_type: WonderType::StatueOfZeus,
+            wonder_side: WonderSide::B,
+        };
+        let cost = board.cost(2).unwrap();
+        assert_eq!(2, cost.ore);
+        assert_eq!(1, cost.loom);
+        assert_eq!(0, cost.wood);
+    }
 }